@@ -0,0 +1,45 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use rust_mazes::{maze::Maze, mazes::*};
+
+const SIZES: [usize; 4] = [32, 128, 512, 2048];
+const SEED: u64 = 0;
+
+/// Times generating a `size` by `size` maze from a fresh reset until it is complete, for
+/// every size in `SIZES`.
+fn bench_generator<M: Maze>(c: &mut Criterion, new: fn(usize, usize) -> M, name: &str) {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+
+    for size in SIZES {
+        group.throughput(Throughput::Elements((size * size) as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{size}x{size}")),
+            &size,
+            |b, &size| {
+                b.iter_batched_ref(
+                    || {
+                        let mut maze = new(size, size);
+                        maze.reset_with_seed(SEED);
+                        maze
+                    },
+                    |maze| maze.finish(),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn generators(c: &mut Criterion) {
+    bench_generator(c, DepthFirstSearch::new, "DepthFirstSearch");
+    bench_generator(c, BinaryTree::new, "BinaryTree");
+    bench_generator(c, HuntAndKill::new, "HuntAndKill");
+    bench_generator(c, Prim::new, "Prim");
+    bench_generator(c, Kruskal::new, "Kruskal");
+    bench_generator(c, AldousBroder::new, "AldousBroder");
+}
+
+criterion_group!(benches, generators);
+criterion_main!(benches);
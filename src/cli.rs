@@ -0,0 +1,494 @@
+use std::{
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use clap::{builder::RangedU64ValueParser, Args, Parser, Subcommand, ValueEnum};
+
+use crate::{
+    config::{default_path, Config},
+    formats::{
+        load_png, parse_ascii, rasterize, save_svg, to_text, write_svg, RasterOptions, SavedMaze,
+        SvgOptions, TextStyle,
+    },
+    helpers::{Node, Pos},
+    solve::solve,
+    stats::MazeStats,
+    theme::Theme,
+    Algorithm,
+};
+
+/// Generates mazes, either watching them being carved in a window or headless.
+///
+/// Without a subcommand it opens the visualizer, taking the same options as `view`.
+#[derive(Parser, Debug)]
+#[command(version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub view: ViewArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Watch every algorithm carve a maze in turn.
+    View(ViewArgs),
+    /// Watch several algorithms carve mazes side by side, step for step.
+    Compare(CompareArgs),
+    /// Generate a maze and write it to a file or print it.
+    Generate(GenerateArgs),
+    /// Find the path through a saved maze.
+    Solve(SolveArgs),
+    /// Compare the shape of the mazes each algorithm makes.
+    Stats(StatsArgs),
+    /// Time how long each algorithm takes to generate a maze.
+    Bench(BenchArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ViewArgs {
+    /// The algorithm to start with instead of a random one.
+    #[arg(short, long)]
+    pub algorithm: Option<Algorithm>,
+    /// A TOML config file. Defaults to `mazes.toml` if there is one. The other options
+    /// override what it sets.
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+    /// The width of the maze in cells.
+    #[arg(long, value_parser = at_least_one())]
+    pub width: Option<usize>,
+    /// The height of the maze in cells.
+    #[arg(long, value_parser = at_least_one())]
+    pub height: Option<usize>,
+    /// The seed of the first maze. Later ones are random.
+    #[arg(short, long)]
+    pub seed: Option<u64>,
+    /// Steps per second for every algorithm, instead of a speed suited to each.
+    #[arg(long, value_parser = at_least_one())]
+    pub speed: Option<usize>,
+    /// The name of a built-in theme or the path to a theme file.
+    #[arg(short, long)]
+    pub theme: Option<String>,
+    /// How many seconds a finished maze stays on screen.
+    #[arg(long)]
+    pub hold: Option<f64>,
+    /// Find your way through every maze once it is finished, instead of after pressing P.
+    #[arg(short, long)]
+    pub play: bool,
+}
+
+impl ViewArgs {
+    /// Loads the config file and applies the options given on the command line over it.
+    pub fn config(&self) -> io::Result<Config> {
+        let mut config = load_config(self.config.as_deref())?;
+
+        if let Some(width) = self.width {
+            config.width = width;
+        }
+        if let Some(height) = self.height {
+            config.height = height;
+        }
+        if let Some(speed) = self.speed {
+            config.speeds = Algorithm::all()
+                .iter()
+                .map(|&algorithm| (algorithm, speed))
+                .collect();
+        }
+        if let Some(theme) = &self.theme {
+            config.theme = Some(theme.clone());
+        }
+        if let Some(hold) = self.hold {
+            config.hold = hold;
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct CompareArgs {
+    /// An algorithm to show, once per viewport. Repeat one to compare it against itself with
+    /// different seeds.
+    #[arg(short, long = "algorithm", required = true)]
+    pub algorithms: Vec<Algorithm>,
+    /// A TOML config file to take the theme, the hold and how mazes are drawn from. Defaults
+    /// to `mazes.toml` if there is one. Its size and speeds are left out, as the viewports are
+    /// sized by the options here.
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+    #[arg(long, default_value_t = 48, value_parser = at_least_one())]
+    pub width: usize,
+    #[arg(long, default_value_t = 32, value_parser = at_least_one())]
+    pub height: usize,
+    /// The size of a cell in pixels.
+    #[arg(long, default_value_t = 8.0)]
+    pub cell_size: f32,
+    /// The seed of the first viewport, counting up for the others. Later mazes are random.
+    #[arg(short, long)]
+    pub seed: Option<u64>,
+    /// Steps per second for every maze. Defaults to the slowest speed of the algorithms
+    /// shown.
+    #[arg(long, value_parser = at_least_one())]
+    pub speed: Option<usize>,
+    /// The name of a built-in theme or the path to a theme file.
+    #[arg(short, long)]
+    pub theme: Option<String>,
+    /// How many seconds the finished mazes stay on screen.
+    #[arg(long)]
+    pub hold: Option<f64>,
+}
+
+impl CompareArgs {
+    /// Loads the config file and applies the options given on the command line over it.
+    pub fn config(&self) -> io::Result<Config> {
+        let mut config = load_config(self.config.as_deref())?;
+
+        config.width = self.width;
+        config.height = self.height;
+        config.cell_size = self.cell_size;
+        if let Some(theme) = &self.theme {
+            config.theme = Some(theme.clone());
+        }
+        if let Some(hold) = self.hold {
+            config.hold = hold;
+        }
+
+        Ok(config)
+    }
+
+    /// How many steps per second each maze runs at.
+    pub fn speed(&self) -> usize {
+        self.speed.unwrap_or_else(|| {
+            self.algorithms
+                .iter()
+                .map(Algorithm::default_speed)
+                .min()
+                .unwrap_or(1)
+        })
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct GenerateArgs {
+    /// A random one when left out.
+    #[arg(short, long)]
+    pub algorithm: Option<Algorithm>,
+    #[arg(long, default_value_t = 20, value_parser = at_least_one())]
+    pub width: usize,
+    #[arg(long, default_value_t = 20, value_parser = at_least_one())]
+    pub height: usize,
+    /// A random one when left out.
+    #[arg(short, long)]
+    pub seed: Option<u64>,
+    /// Where to write the maze. It is printed as text when left out.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// Taken from the extension of the output when left out.
+    #[arg(short, long)]
+    pub format: Option<Format>,
+    /// The name of a built-in theme or the path to a theme file, for PNG output.
+    #[arg(short, long)]
+    pub theme: Option<String>,
+}
+
+/// What `generate` writes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum Format {
+    Png,
+    Svg,
+    Json,
+    Ascii,
+    Unicode,
+}
+
+impl Format {
+    /// Guesses the format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(Format::Png),
+            "svg" => Some(Format::Svg),
+            "json" => Some(Format::Json),
+            "txt" => Some(Format::Ascii),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct SolveArgs {
+    /// A maze saved by `generate` as JSON, ASCII text or PNG.
+    pub input: PathBuf,
+    /// The cell to start from as `x,y`. Defaults to the saved start, or the top left cell.
+    #[arg(long, value_parser = parse_pos)]
+    pub from: Option<Pos>,
+    /// The cell to reach as `x,y`. Defaults to the saved goal, or the bottom right cell.
+    #[arg(long, value_parser = parse_pos)]
+    pub to: Option<Pos>,
+    /// Writes the maze with the path drawn on it as an SVG.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    /// Every algorithm when left out.
+    #[arg(short, long)]
+    pub algorithm: Option<Algorithm>,
+    #[arg(long, default_value_t = 20, value_parser = at_least_one())]
+    pub width: usize,
+    #[arg(long, default_value_t = 20, value_parser = at_least_one())]
+    pub height: usize,
+    /// How many mazes to average over, seeded from 0 up.
+    #[arg(short, long, default_value_t = 100)]
+    pub count: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+    /// Every algorithm when left out.
+    #[arg(short, long)]
+    pub algorithm: Option<Algorithm>,
+    #[arg(long, default_value_t = 100, value_parser = at_least_one())]
+    pub width: usize,
+    #[arg(long, default_value_t = 100, value_parser = at_least_one())]
+    pub height: usize,
+    /// How many mazes to time, seeded from 0 up.
+    #[arg(short, long, default_value_t = 10)]
+    pub iterations: u64,
+}
+
+/// Loads the config file at `path`, or `mazes.toml` if there is one, or the defaults.
+fn load_config(path: Option<&Path>) -> io::Result<Config> {
+    match path {
+        Some(path) => Config::load(path),
+        None if default_path().exists() => Config::load(default_path()),
+        None => Ok(Config::default()),
+    }
+}
+
+/// Parses a width, height or speed, none of which can be 0.
+fn at_least_one() -> RangedU64ValueParser<usize> {
+    RangedU64ValueParser::new().range(1..)
+}
+
+/// Parses a cell given as `x,y`.
+pub fn parse_pos(text: &str) -> Result<Pos, String> {
+    let (x, y) = text
+        .split_once(',')
+        .ok_or_else(|| format!("expected x,y but got {text:?}"))?;
+    let coordinate = |value: &str| {
+        value
+            .trim()
+            .parse::<usize>()
+            .map_err(|err| format!("{value:?} is not a coordinate: {err}"))
+    };
+    Ok(Pos::new(coordinate(x)?, coordinate(y)?))
+}
+
+pub fn generate(args: &GenerateArgs) -> Result<(), Box<dyn Error>> {
+    let algorithm = args.algorithm.unwrap_or_else(rand::random);
+    let mut maze = algorithm.new_maze(args.width, args.height);
+    maze.reset_with_seed(args.seed.unwrap_or_else(rand::random));
+    maze.finish();
+
+    let format = match (args.format, &args.output) {
+        (Some(format), _) => format,
+        (None, Some(path)) => Format::from_path(path).ok_or_else(|| {
+            format!(
+                "can't tell the format of {} from its extension, pass --format",
+                path.display()
+            )
+        })?,
+        (None, None) => Format::Ascii,
+    };
+
+    let mut bytes = vec![];
+    match format {
+        Format::Png => {
+            let mut options = RasterOptions::default();
+            if let Some(theme) = &args.theme {
+                options = options.themed(&Theme::find(theme)?);
+            }
+            rasterize(maze.nodes(), &options).write_png(&mut bytes)?;
+        }
+        Format::Svg => write_svg(
+            maze.nodes(),
+            &SvgOptions::default().with_corners(maze.nodes()),
+            &mut bytes,
+        )?,
+        Format::Json => SavedMaze::from_maze(maze.as_ref(), algorithm).write_json(&mut bytes)?,
+        Format::Ascii => bytes = to_text(maze.nodes(), TextStyle::Ascii).into_bytes(),
+        Format::Unicode => bytes = to_text(maze.nodes(), TextStyle::Unicode).into_bytes(),
+    }
+
+    match &args.output {
+        Some(path) => fs::write(path, bytes)?,
+        None => io::stdout().write_all(&bytes)?,
+    }
+
+    eprintln!("Generated with {algorithm:?}, seed {}", maze.seed());
+    Ok(())
+}
+
+/// Loads a maze by the extension of `path`, along with its saved start and goal.
+pub fn load_maze(path: &Path) -> Result<SavedMaze, Box<dyn Error>> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+
+    Ok(match extension.as_deref() {
+        Some("png") => SavedMaze::new(load_png(path)?),
+        Some("txt") => SavedMaze::new(parse_ascii(&fs::read_to_string(path)?)?),
+        _ => SavedMaze::load(path)?,
+    })
+}
+
+pub fn solve_maze(args: &SolveArgs) -> Result<(), Box<dyn Error>> {
+    let maze = load_maze(&args.input)?;
+    if maze.width() == 0 || maze.height() == 0 {
+        return Err(format!("{} has no cells", args.input.display()).into());
+    }
+
+    let start = args.from.or(maze.start).unwrap_or(Pos::new(0, 0));
+    let goal = args
+        .to
+        .or(maze.goal)
+        .unwrap_or(Pos::new(maze.width() - 1, maze.height() - 1));
+
+    for pos in [start, goal] {
+        if pos.x >= maze.width() || pos.y >= maze.height() {
+            return Err(format!(
+                "{},{} is outside the {}x{} maze",
+                pos.x,
+                pos.y,
+                maze.width(),
+                maze.height()
+            )
+            .into());
+        }
+    }
+
+    let path = solve(&maze.nodes, start, goal).ok_or_else(|| {
+        format!(
+            "there is no path from {},{} to {},{}",
+            start.x, start.y, goal.x, goal.y
+        )
+    })?;
+
+    println!("{} cells", path.len());
+    let cells: Vec<String> = path
+        .iter()
+        .map(|pos| format!("{},{}", pos.x, pos.y))
+        .collect();
+    println!("{}", cells.join(" "));
+
+    if let Some(output) = &args.output {
+        let options = SvgOptions {
+            entrance: Some(start),
+            exit: Some(goal),
+            show_solution: true,
+            ..SvgOptions::default()
+        };
+        save_svg(&maze.nodes, &options, output)?;
+    }
+
+    Ok(())
+}
+
+/// `MazeStats` summed over many mazes.
+#[derive(Default)]
+struct Totals {
+    mazes: usize,
+    stats: MazeStats,
+    solution_length: usize,
+}
+
+impl Totals {
+    fn add(&mut self, nodes: &[Vec<Node>]) {
+        let stats = MazeStats::of(nodes);
+        self.mazes += 1;
+        self.stats.cells += stats.cells;
+        self.stats.dead_ends += stats.dead_ends;
+        self.stats.straights += stats.straights;
+        self.stats.turns += stats.turns;
+        self.stats.junctions += stats.junctions;
+        self.solution_length += stats.solution_length.unwrap_or(0);
+    }
+
+    /// `count` as a percentage of every cell.
+    fn percent(&self, count: usize) -> f64 {
+        count as f64 / self.stats.cells.max(1) as f64 * 100.0
+    }
+}
+
+pub fn stats(args: &StatsArgs) -> Result<(), Box<dyn Error>> {
+    println!(
+        "{:<20} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "algorithm", "dead ends", "straights", "turns", "junctions", "solution"
+    );
+
+    for algorithm in selected(args.algorithm) {
+        let mut maze = algorithm.new_maze(args.width, args.height);
+        let mut totals = Totals::default();
+        for seed in 0..args.count {
+            maze.reset_with_seed(seed);
+            maze.finish();
+            totals.add(maze.nodes());
+        }
+
+        let stats = totals.stats;
+        println!(
+            "{:<20} {:>9.1}% {:>9.1}% {:>9.1}% {:>9.1}% {:>9.1}%",
+            format!("{algorithm:?}"),
+            totals.percent(stats.dead_ends),
+            totals.percent(stats.straights),
+            totals.percent(stats.turns),
+            totals.percent(stats.junctions),
+            totals.percent(totals.solution_length),
+        );
+    }
+
+    Ok(())
+}
+
+pub fn bench(args: &BenchArgs) -> Result<(), Box<dyn Error>> {
+    println!("{:<20} {:>12} {:>14}", "algorithm", "per maze", "steps/s");
+
+    for algorithm in selected(args.algorithm) {
+        let mut maze = algorithm.new_maze(args.width, args.height);
+        let mut elapsed = Duration::ZERO;
+        let mut steps = 0;
+        for seed in 0..args.iterations {
+            maze.reset_with_seed(seed);
+
+            let started = Instant::now();
+            while !maze.complete() {
+                maze.generate();
+                steps += 1;
+            }
+            elapsed += started.elapsed();
+        }
+
+        let per_maze = elapsed / args.iterations.max(1) as u32;
+        let rate = steps as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+        println!(
+            "{:<20} {:>12} {:>14.0}",
+            format!("{algorithm:?}"),
+            format!("{per_maze:.2?}"),
+            rate
+        );
+    }
+
+    Ok(())
+}
+
+/// The algorithm passed on the command line, or all of them.
+fn selected(algorithm: Option<Algorithm>) -> Vec<Algorithm> {
+    algorithm.map_or(Algorithm::all().to_vec(), |algorithm| vec![algorithm])
+}
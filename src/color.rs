@@ -0,0 +1,56 @@
+/// A color with 8-bit red, green, blue and alpha channels.
+///
+/// Exports and themes use it without depending on raylib. With the `viewer` feature it turns
+/// into raylib's colors, so it can be passed straight to raylib's drawing functions.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::new(0, 0, 0, 255);
+    pub const WHITE: Color = Color::new(255, 255, 255, 255);
+    pub const RAYWHITE: Color = Color::new(245, 245, 245, 255);
+    pub const GRAY: Color = Color::new(130, 130, 130, 255);
+    pub const RED: Color = Color::new(230, 41, 55, 255);
+    pub const GREEN: Color = Color::new(0, 228, 48, 255);
+    pub const BLUE: Color = Color::new(0, 121, 241, 255);
+    pub const DARKBLUE: Color = Color::new(0, 82, 172, 255);
+    pub const SKYBLUE: Color = Color::new(102, 191, 255, 255);
+    pub const PURPLE: Color = Color::new(200, 122, 255, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// The same color with an opacity of `alpha`, from 0 for transparent to 1 for opaque, like
+    /// raylib's `Fade`.
+    pub fn fade(self, alpha: f32) -> Self {
+        Self {
+            a: (255.0 * alpha.clamp(0.0, 1.0)) as u8,
+            ..self
+        }
+    }
+}
+
+#[cfg(feature = "viewer")]
+impl From<Color> for raylib::prelude::Color {
+    fn from(color: Color) -> Self {
+        Self::new(color.r, color.g, color.b, color.a)
+    }
+}
+
+#[cfg(feature = "viewer")]
+impl From<Color> for raylib::ffi::Color {
+    fn from(color: Color) -> Self {
+        Self {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+            a: color.a,
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use std::time::{Duration, Instant};
+
+use raylib::prelude::*;
+
+use crate::{
+    maze::Maze,
+    pace::Pacer,
+    render::{RenderOptions, Renderer},
+    timeline::{Timeline, CHECKPOINT_CELLS},
+    viewer::{draw_panel, DEFAULT_HOLD, PANEL_FONT_SIZE},
+    Algorithm,
+};
+
+/// The space between viewports and around the edge of the window, in pixels.
+pub const GAP: i32 = 8;
+
+/// The height of the line above each viewport naming its generator, in pixels.
+pub const LABEL_HEIGHT: i32 = PANEL_FONT_SIZE + 8;
+
+/// How far the speed can be scaled up or down.
+const MAX_SPEED_SCALE: f32 = 64.0;
+
+/// The bindings the help overlay lists.
+const BINDINGS: [(&str, &str); 8] = [
+    ("Space", "pause / resume"),
+    (
+        "Left / Right",
+        "step every maze backwards / forwards while paused",
+    ),
+    ("Up / Down, wheel", "speed up / slow down"),
+    ("R", "regenerate with new seeds"),
+    ("O", "toggle highlights"),
+    ("L", "toggle the labels"),
+    ("H / F1", "toggle this help"),
+    ("Esc", "quit"),
+];
+
+/// Where the top left corner of each of `count` mazes of `size` pixels goes when they are laid
+/// out in a grid that is as close to square as it can be, each with a label above it. Also
+/// returns the size of the whole grid, gaps included.
+pub fn layout(count: usize, size: (i32, i32)) -> (Vec<(i32, i32)>, (i32, i32)) {
+    if count == 0 {
+        return (vec![], (0, 0));
+    }
+
+    let columns = (count as f64).sqrt().ceil() as usize;
+    let rows = count.div_ceil(columns);
+
+    let cell = (size.0 + GAP, LABEL_HEIGHT + size.1 + GAP);
+    let origins = (0..count)
+        .map(|i| {
+            let (column, row) = ((i % columns) as i32, (i / columns) as i32);
+            (GAP + column * cell.0, GAP + row * cell.1 + LABEL_HEIGHT)
+        })
+        .collect();
+
+    (
+        origins,
+        (GAP + columns as i32 * cell.0, GAP + rows as i32 * cell.1),
+    )
+}
+
+/// One of the mazes being compared and where it is drawn.
+pub struct Viewport {
+    pub algorithm: Algorithm,
+    /// The maze and its history. Its position is how many steps this viewport has run.
+    pub timeline: Timeline<Box<dyn Maze>>,
+    pub renderer: Renderer,
+    /// The top left corner of the maze in the window, below its label.
+    pub origin: (i32, i32),
+}
+
+/// Runs several generators side by side on grids of the same size, advancing every unfinished
+/// one by a step at a time so they can be compared step for step. Once they are all finished
+/// they stay on screen for `hold`, then start over with new seeds.
+pub struct Comparison {
+    pub viewports: Vec<Viewport>,
+    /// How long the finished mazes stay on screen before new ones start.
+    pub hold: Duration,
+    /// The size of the window the viewports fill, in pixels.
+    size: (i32, i32),
+    /// How much unpaused time is left before new mazes start, once all are finished.
+    holding: Option<Duration>,
+    pacer: Pacer,
+    last_frame: Instant,
+    paused: bool,
+    show_help: bool,
+    show_labels: bool,
+    /// What the speed is multiplied by, always a power of two.
+    speed_scale: f32,
+}
+
+impl Comparison {
+    /// Lays out a `width` by `height` maze for each of `algorithms`, which may repeat to
+    /// compare one generator against itself. Each starts on a random seed.
+    pub fn new(
+        algorithms: &[Algorithm],
+        width: usize,
+        height: usize,
+        options: RenderOptions,
+    ) -> Self {
+        let (origins, size) = layout(algorithms.len(), options.size(width, height));
+        // The viewports share the memory one timeline would take.
+        let checkpoint_cells = CHECKPOINT_CELLS / algorithms.len().max(1);
+        let viewports = algorithms
+            .iter()
+            .zip(origins)
+            .map(|(&algorithm, origin)| {
+                let maze = algorithm.new_maze(width, height);
+                let mut timeline = Timeline::with_checkpoint_cells(maze, checkpoint_cells);
+                timeline.reset();
+                Viewport {
+                    algorithm,
+                    timeline,
+                    renderer: Renderer::new(options.clone()),
+                    origin,
+                }
+            })
+            .collect();
+
+        Self {
+            viewports,
+            hold: DEFAULT_HOLD,
+            size,
+            holding: None,
+            pacer: Pacer::new(),
+            last_frame: Instant::now(),
+            paused: false,
+            show_help: false,
+            show_labels: true,
+            speed_scale: 1.0,
+        }
+    }
+
+    /// The size of the window the viewports fill, in pixels.
+    pub fn size(&self) -> (i32, i32) {
+        self.size
+    }
+
+    /// Starts every viewport over, the first with `seed`, the next with `seed + 1` and so on,
+    /// so a generator compared against itself makes different mazes.
+    pub fn reset_with_seed(&mut self, seed: u64) {
+        for (i, viewport) in self.viewports.iter_mut().enumerate() {
+            viewport
+                .timeline
+                .reset_with_seed(seed.wrapping_add(i as u64));
+        }
+        self.restart();
+    }
+
+    /// Starts every viewport over with a random seed.
+    pub fn reset(&mut self) {
+        for viewport in &mut self.viewports {
+            viewport.timeline.reset();
+        }
+        self.restart();
+    }
+
+    /// Runs one step of every maze that isn't finished yet. Returns whether any of them did.
+    pub fn step_forward(&mut self) -> bool {
+        let mut stepped = false;
+        for viewport in &mut self.viewports {
+            stepped |= viewport.timeline.step_forward();
+        }
+        stepped
+    }
+
+    /// Takes back the last step of every maze that was still running at that point. Returns
+    /// whether any of them did.
+    pub fn step_back(&mut self) -> bool {
+        let Some(position) = self
+            .viewports
+            .iter()
+            .map(|viewport| viewport.timeline.position())
+            .max()
+        else {
+            return false;
+        };
+
+        let mut stepped = false;
+        for viewport in &mut self.viewports {
+            if position > 0 && viewport.timeline.position() == position {
+                stepped |= viewport.timeline.step_back();
+            }
+        }
+        stepped
+    }
+
+    /// Whether every maze is finished.
+    pub fn complete(&self) -> bool {
+        self.viewports
+            .iter()
+            .all(|viewport| viewport.timeline.maze().complete())
+    }
+
+    /// Advances the mazes by the time since the last frame and draws them. `speed` is how
+    /// many steps per second each maze runs at before the user speeds it up or slows it down.
+    pub fn update(&mut self, speed: usize, rl: &mut RaylibHandle, thread: &RaylibThread) {
+        let now = Instant::now();
+        let elapsed = now - self.last_frame;
+        self.last_frame = now;
+
+        self.handle_input(rl);
+
+        match self.holding {
+            None => {
+                if self.paused {
+                    self.pacer.reset();
+                } else {
+                    let rate = speed as f64 * self.speed_scale as f64;
+                    for _ in 0..self.pacer.advance(elapsed, rate) {
+                        if !self.step_forward() {
+                            break;
+                        }
+                    }
+                }
+
+                if self.complete() && !self.paused {
+                    for viewport in &self.viewports {
+                        println!(
+                            "{} took {} steps",
+                            viewport.algorithm,
+                            viewport.timeline.position()
+                        );
+                    }
+                    self.holding = Some(self.hold);
+                }
+            }
+            Some(remaining) => {
+                if !self.complete() {
+                    // Stepped back into the generation.
+                    self.holding = None;
+                } else if !self.paused {
+                    match remaining.checked_sub(elapsed) {
+                        Some(remaining) if !remaining.is_zero() => {
+                            self.holding = Some(remaining);
+                        }
+                        _ => self.reset(),
+                    }
+                }
+            }
+        }
+
+        for viewport in &mut self.viewports {
+            let changes = viewport.timeline.take_changes();
+            if let Err(err) =
+                viewport
+                    .renderer
+                    .render(rl, thread, viewport.timeline.maze(), changes.as_deref())
+            {
+                println!("Could not render to a texture, drawing every cell each frame: {err}");
+            }
+        }
+
+        let mut d = rl.begin_drawing(thread);
+        let Some(theme) = self
+            .viewports
+            .first()
+            .map(|viewport| viewport.renderer.options.theme.clone())
+        else {
+            return;
+        };
+        d.clear_background(theme.background);
+
+        for viewport in &self.viewports {
+            let (x, y) = viewport.origin;
+            viewport.renderer.draw(
+                &mut d,
+                viewport.timeline.maze(),
+                Vector2::new(x as f32, y as f32),
+            );
+
+            if self.show_labels {
+                let maze = viewport.timeline.maze();
+                let done = if maze.complete() { ", done" } else { "" };
+                let label = format!(
+                    "{}: {} steps{done}",
+                    viewport.algorithm,
+                    viewport.timeline.position()
+                );
+                let color = if maze.complete() {
+                    theme.passage
+                } else {
+                    theme.active
+                };
+                d.draw_text(&label, x, y - LABEL_HEIGHT + 4, PANEL_FONT_SIZE, color);
+            }
+        }
+
+        if self.show_help {
+            draw_panel(&mut d, &theme, &BINDINGS, false);
+        }
+    }
+
+    /// Starts the clocks over for new mazes.
+    fn restart(&mut self) {
+        self.holding = None;
+        self.pacer.reset();
+    }
+
+    fn handle_input(&mut self, rl: &RaylibHandle) {
+        if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
+            self.paused = !self.paused;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_H) || rl.is_key_pressed(KeyboardKey::KEY_F1) {
+            self.show_help = !self.show_help;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_L) {
+            self.show_labels = !self.show_labels;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_O) {
+            for viewport in &mut self.viewports {
+                let options = &mut viewport.renderer.options;
+                options.highlights = !options.highlights;
+            }
+        }
+
+        let wheel = rl.get_mouse_wheel_move();
+        if rl.is_key_pressed(KeyboardKey::KEY_UP) || wheel > 0.0 {
+            self.speed_scale = (self.speed_scale * 2.0).min(MAX_SPEED_SCALE);
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_DOWN) || wheel < 0.0 {
+            self.speed_scale = (self.speed_scale / 2.0).max(1.0 / MAX_SPEED_SCALE);
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_R) {
+            self.reset();
+        }
+
+        if self.paused {
+            if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+                self.step_forward();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+                self.step_back();
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{render::RenderOptions, theme::Theme, Algorithm, GRID_HEIGHT, GRID_WIDTH, NODE_SIZE};
+
+/// Everything about the visualizer that can be set without recompiling, loaded from a TOML
+/// file:
+///
+/// ```toml
+/// width = 96
+/// height = 51
+/// cell_size = 20.0
+/// wall_thickness = 2.0
+/// inset = 1.0
+/// roundness = 0.5
+/// anti_alias = true
+/// theme = "dark"
+/// hold = 5.0
+///
+/// [speeds]
+/// aldous-broder = 40000
+///
+/// [playlist]
+/// order = "weighted"
+/// algorithms = ["prim", "kruskal", "aldous-broder"]
+/// weights = { prim = 3 }
+/// ```
+///
+/// Anything a file leaves out keeps its default.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The size of the maze in cells.
+    pub width: usize,
+    pub height: usize,
+    /// The size of a cell on screen in pixels.
+    pub cell_size: f32,
+    /// How mazes are drawn, as in `RenderOptions`.
+    pub wall_thickness: f32,
+    pub inset: f32,
+    pub roundness: f32,
+    pub anti_alias: bool,
+    /// The name of a built-in theme or the path to a theme file, relative to the config file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// How many seconds a finished maze stays on screen.
+    pub hold: f64,
+    /// Steps per second for the algorithms that should not run at their default speed.
+    pub speeds: BTreeMap<Algorithm, usize>,
+    pub playlist: Playlist,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            width: GRID_WIDTH,
+            height: GRID_HEIGHT,
+            cell_size: NODE_SIZE as f32,
+            wall_thickness: 1.0,
+            inset: 0.0,
+            roundness: 0.0,
+            anti_alias: false,
+            theme: None,
+            hold: 2.0,
+            speeds: BTreeMap::new(),
+            playlist: Playlist::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn speed(&self, algorithm: Algorithm) -> usize {
+        self.speeds
+            .get(&algorithm)
+            .copied()
+            .unwrap_or_else(|| algorithm.default_speed())
+    }
+
+    /// How to draw mazes, in the default theme.
+    pub fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            cell_size: self.cell_size,
+            wall_thickness: self.wall_thickness,
+            inset: self.inset,
+            roundness: self.roundness,
+            anti_alias: self.anti_alias,
+            ..RenderOptions::default()
+        }
+    }
+
+    pub fn theme(&self) -> io::Result<Option<Theme>> {
+        self.theme.as_deref().map(Theme::find).transpose()
+    }
+
+    /// Fails if the maze would have no cells or no size on screen, an algorithm would never
+    /// step, a setting for how it is drawn is out of range, the hold isn't a valid duration or
+    /// the playlist has nothing to play.
+    pub fn check(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err(format!(
+                "the maze is {}x{}, but it needs at least one cell each way",
+                self.width, self.height
+            ));
+        }
+        if let Some((algorithm, _)) = self.speeds.iter().find(|&(_, &speed)| speed == 0) {
+            return Err(format!(
+                "the speed of {algorithm} is 0, but it needs at least one step per second"
+            ));
+        }
+        if !self.cell_size.is_finite() || self.cell_size <= 0.0 {
+            return Err(format!(
+                "the cell size must be a finite, positive number of pixels, not {:?}",
+                self.cell_size
+            ));
+        }
+        for (name, value) in [
+            ("wall thickness", self.wall_thickness),
+            ("inset", self.inset),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!(
+                    "the {name} must be a finite, non-negative number of pixels, not {value:?}"
+                ));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.roundness) {
+            return Err(format!(
+                "the roundness must be between 0 and 1, not {:?}",
+                self.roundness
+            ));
+        }
+        hold(self.hold)?;
+        self.playlist.check()
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(text).map_err(|err| err.to_string())?;
+        config.check()?;
+        Ok(config)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("a config is always valid TOML")
+    }
+
+    /// Loads a config from a TOML file, resolving a theme path against the file's directory.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut config = Self::from_toml(&fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        if let Some(theme) = &mut config.theme {
+            if Theme::named(theme).is_none() && Path::new(theme).is_relative() {
+                let dir = path.parent().unwrap_or(Path::new(""));
+                *theme = dir.join(&*theme).to_string_lossy().into_owned();
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// How the visualizer picks the next algorithm.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Order {
+    /// Plays `algorithms` in the order they are listed, over and over.
+    #[default]
+    Sequential,
+    /// Plays every algorithm once in a random order before any plays again.
+    Shuffled,
+    /// Picks each next algorithm at random, by `weights`.
+    Weighted,
+}
+
+/// Which algorithms the visualizer plays, and in what order.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Playlist {
+    pub order: Order,
+    /// Only these algorithms are played.
+    pub algorithms: Vec<Algorithm>,
+    /// How likely each algorithm is to be picked with `Order::Weighted`, relative to the
+    /// others. Algorithms left out have a weight of 1.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub weights: BTreeMap<Algorithm, f64>,
+    /// What is left to play of the current round when shuffled.
+    #[serde(skip)]
+    round: Vec<Algorithm>,
+    /// The index in `algorithms` of what played last when sequential, so an algorithm listed
+    /// more than once doesn't send the playlist back to its first entry.
+    #[serde(skip)]
+    cursor: usize,
+}
+
+impl Default for Playlist {
+    fn default() -> Self {
+        Self::new(Order::Sequential, Algorithm::all().to_vec())
+    }
+}
+
+impl Playlist {
+    pub fn new(order: Order, algorithms: Vec<Algorithm>) -> Self {
+        Self {
+            order,
+            algorithms,
+            weights: BTreeMap::new(),
+            round: vec![],
+            cursor: 0,
+        }
+    }
+
+    /// Fails if the playlist has nothing to play.
+    pub fn check(&self) -> Result<(), String> {
+        if self.algorithms.is_empty() {
+            return Err("the playlist has no algorithms".to_string());
+        }
+        if let Some((algorithm, weight)) = self
+            .weights
+            .iter()
+            .find(|(_, weight)| !weight.is_finite() || **weight < 0.0)
+        {
+            return Err(format!(
+                "{algorithm:?} has a weight of {weight}, but weights must be a finite, \
+                 non-negative number"
+            ));
+        }
+        if self.order == Order::Weighted && self.algorithms.iter().all(|a| self.weight(*a) == 0.0) {
+            return Err("every algorithm in the playlist has a weight of 0".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn weight(&self, algorithm: Algorithm) -> f64 {
+        self.weights.get(&algorithm).copied().unwrap_or(1.0)
+    }
+
+    /// Picks the algorithm to play after `current`, or the first one to play when there is
+    /// none yet.
+    ///
+    /// # Panics
+    /// If `check` fails.
+    pub fn next<R: Rng + ?Sized>(&mut self, current: Option<Algorithm>, rng: &mut R) -> Algorithm {
+        match self.order {
+            Order::Sequential => {
+                let index = match current {
+                    None => 0,
+                    Some(current) if self.algorithms.get(self.cursor) == Some(&current) => {
+                        self.cursor + 1
+                    }
+                    // Picked by hand, so carry on from where it is listed, if it is.
+                    Some(current) => self
+                        .algorithms
+                        .iter()
+                        .position(|&a| a == current)
+                        .map_or(0, |index| index + 1),
+                };
+                self.cursor = index % self.algorithms.len();
+                self.algorithms[self.cursor]
+            }
+            Order::Shuffled => {
+                if self.round.is_empty() {
+                    self.round = self.algorithms.clone();
+                    self.round.shuffle(rng);
+                    // Rounds are played from the back, so don't start one with what just
+                    // played.
+                    if self.round.len() > 1 && self.round.last() == current.as_ref() {
+                        self.round.swap(0, self.algorithms.len() - 1);
+                    }
+                }
+                self.round.pop().expect("the playlist has algorithms")
+            }
+            Order::Weighted => {
+                let weights = self.algorithms.iter().map(|&a| self.weight(a));
+                let index = WeightedIndex::new(weights)
+                    .expect("the playlist has algorithms with a positive weight")
+                    .sample(rng);
+                self.algorithms[index]
+            }
+        }
+    }
+}
+
+/// Turns a hold given in seconds into a duration, failing for negative, infinite or NaN
+/// values and ones too large to represent.
+pub fn hold(seconds: f64) -> Result<Duration, String> {
+    Duration::try_from_secs_f64(seconds).map_err(|_| {
+        format!("the hold must be a finite, non-negative number of seconds, not {seconds:?}")
+    })
+}
+
+/// The config file the visualizer loads when none is given, if it exists.
+pub fn default_path() -> PathBuf {
+    PathBuf::from("mazes.toml")
+}
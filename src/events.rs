@@ -0,0 +1,43 @@
+use crate::helpers::Pos;
+
+/// A change a single `Maze::step` made to the maze or the generator's working state.
+///
+/// Every cell whose walls, visited state or highlight a step changes is named by one of the
+/// events it emits, so the cells to redraw after a step are the ones in `Event::cells`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    /// The wall between two neighboring cells was removed.
+    Carved { from: Pos, to: Pos },
+    /// The cell was reached for the first time.
+    Visited(Pos),
+    /// The cell was added to the stack or frontier of cells to carve from.
+    Pushed(Pos),
+    /// The cell was taken off the stack or frontier.
+    Popped(Pos),
+    /// Every cell in set `from` joined set `into`, as they are connected now.
+    Merged { from: usize, into: usize },
+    /// The cell the generator works from moved, without carving anything by itself.
+    Moved(Pos),
+    /// The cell joined the trail of cells the generator worked on recently.
+    Trailed(Pos),
+    /// The cell is no longer highlighted as recently worked on, as it fell off the end of the
+    /// trail or the generator moved on from it.
+    Faded(Pos),
+}
+
+impl Event {
+    /// The cells the event is about.
+    pub fn cells(self) -> impl Iterator<Item = Pos> {
+        let cells = match self {
+            Event::Carved { from, to } => [Some(from), Some(to)],
+            Event::Visited(pos)
+            | Event::Pushed(pos)
+            | Event::Popped(pos)
+            | Event::Moved(pos)
+            | Event::Trailed(pos)
+            | Event::Faded(pos) => [Some(pos), None],
+            Event::Merged { .. } => [None, None],
+        };
+        cells.into_iter().flatten()
+    }
+}
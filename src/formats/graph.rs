@@ -0,0 +1,153 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::helpers::{Node, Pos};
+
+/// A passage between two cells of the graph. `length` is the number of steps it takes to walk
+/// from one end to the other, which is only ever more than 1 once corridors are collapsed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Edge {
+    pub from: Pos,
+    pub to: Pos,
+    pub length: usize,
+}
+
+/// The cells of a maze and the passages between them.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PassageGraph {
+    /// Cells in column-major order, like `nodes`.
+    pub nodes: Vec<Pos>,
+    pub edges: Vec<Edge>,
+}
+
+/// Builds the graph of the passages carved into `nodes`, with one graph node per cell and
+/// one edge per open wall.
+///
+/// With `collapse_corridors`, only junctions and dead ends are kept as graph nodes and every
+/// corridor between them becomes a single edge. Loops made up of nothing but corridor cells
+/// have no junction to start from and are left out, which never happens in a perfect maze.
+pub fn passage_graph(nodes: &[Vec<Node>], collapse_corridors: bool) -> PassageGraph {
+    let cells = nodes
+        .iter()
+        .enumerate()
+        .flat_map(|(x, column)| (0..column.len()).map(move |y| Pos::new(x, y)));
+
+    if !collapse_corridors {
+        let mut edges = vec![];
+        for pos in cells.clone() {
+            for neighbor in pos.open_neighbors(nodes) {
+                let next_pos = neighbor.get_pos();
+                if pos < next_pos {
+                    edges.push(Edge {
+                        from: pos,
+                        to: next_pos,
+                        length: 1,
+                    });
+                }
+            }
+        }
+
+        return PassageGraph {
+            nodes: cells.collect(),
+            edges,
+        };
+    }
+
+    let is_corridor = |pos: Pos| pos.open_neighbors(nodes).len() == 2;
+    let graph_nodes: Vec<Pos> = cells.filter(|&pos| !is_corridor(pos)).collect();
+
+    let mut edges = vec![];
+    for &from in &graph_nodes {
+        for neighbor in from.open_neighbors(nodes) {
+            let first = neighbor.get_pos();
+            let (mut previous, mut current, mut length) = (from, first, 1);
+
+            while is_corridor(current) {
+                let next = current
+                    .open_neighbors(nodes)
+                    .into_iter()
+                    .map(|neighbor| neighbor.get_pos())
+                    .find(|&pos| pos != previous)
+                    .expect("a corridor cell has two open neighbors");
+                (previous, current) = (current, next);
+                length += 1;
+            }
+
+            // Every corridor is walked once from each end, so only keep one of the two walks.
+            if (from, first) <= (current, previous) {
+                edges.push(Edge {
+                    from,
+                    to: current,
+                    length,
+                });
+            }
+        }
+    }
+
+    PassageGraph {
+        nodes: graph_nodes,
+        edges,
+    }
+}
+
+impl PassageGraph {
+    /// Writes the graph in Graphviz's DOT language. Every node is pinned to its cell, so
+    /// `neato -n` lays it out like the maze itself.
+    pub fn write_dot<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "graph maze {{")?;
+        writeln!(writer, "    node [shape=point];")?;
+
+        for pos in &self.nodes {
+            writeln!(
+                writer,
+                r#"    {} [pos="{},{}!"];"#,
+                dot_id(*pos),
+                pos.x * 72,
+                // Graphviz puts the origin at the bottom left.
+                -(pos.y as i64 * 72)
+            )?;
+        }
+
+        for edge in &self.edges {
+            write!(writer, "    {} -- {}", dot_id(edge.from), dot_id(edge.to))?;
+            if edge.length > 1 {
+                write!(
+                    writer,
+                    r#" [label="{}", weight={}]"#,
+                    edge.length, edge.length
+                )?;
+            }
+            writeln!(writer, ";")?;
+        }
+
+        writeln!(writer, "}}")?;
+        writer.flush()
+    }
+
+    /// Writes one edge per line as `x y x y length`, the format most graph tools read.
+    pub fn write_edge_list<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for edge in &self.edges {
+            writeln!(
+                writer,
+                "{} {} {} {} {}",
+                edge.from.x, edge.from.y, edge.to.x, edge.to.y, edge.length
+            )?;
+        }
+        writer.flush()
+    }
+
+    pub fn save_dot<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_dot(BufWriter::new(File::create(path)?))
+    }
+
+    pub fn save_edge_list<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_edge_list(BufWriter::new(File::create(path)?))
+    }
+}
+
+fn dot_id(pos: Pos) -> String {
+    format!("c{}_{}", pos.x, pos.y)
+}
@@ -0,0 +1,131 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{self, Read},
+    path::Path,
+};
+
+use crate::{formats::raster::Canvas, helpers::Node};
+
+/// Why `from_canvas` could not find a maze in an image.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportError {
+    /// The image has no dark pixels at all.
+    NoWalls,
+    /// The walls do not line up on a regular grid with passages wider than the walls.
+    Grid,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::NoWalls => write!(f, "the image has no walls"),
+            ImportError::Grid => write!(f, "the walls do not line up on a grid of cells"),
+        }
+    }
+}
+
+impl Error for ImportError {}
+
+/// Reconstructs the grid of a maze drawn with dark walls on light passages, like `rasterize`
+/// draws them. Block-style mazes, where walls and passages are squares of the same size, are
+/// the special case of walls half as thick as the cells.
+///
+/// The cell size and wall thickness are detected from where the walls start, and any margin
+/// around the walls is ignored. Gaps in the outer wall, like an entrance and exit, are closed,
+/// since the grid has no way to represent them on every side.
+pub fn from_canvas(canvas: &Canvas) -> Result<Vec<Vec<Node>>, ImportError> {
+    let is_wall = |x: usize, y: usize| {
+        let color = canvas.get_pixel(x, y);
+        // Perceived brightness, so dark colored walls count as well as black ones.
+        (299 * color.r as u32 + 587 * color.g as u32 + 114 * color.b as u32) / 1000 < 128
+            && color.a >= 128
+    };
+
+    let (mut left, mut top) = (usize::MAX, usize::MAX);
+    let (mut right, mut bottom) = (0, 0);
+    for y in 0..canvas.height() {
+        for x in 0..canvas.width() {
+            if is_wall(x, y) {
+                left = left.min(x);
+                top = top.min(y);
+                right = right.max(x + 1);
+                bottom = bottom.max(y + 1);
+            }
+        }
+    }
+    if left == usize::MAX {
+        return Err(ImportError::NoWalls);
+    }
+
+    let maze_width = right - left;
+    let maze_height = bottom - top;
+    let is_wall = |x: usize, y: usize| is_wall(left + x, top + y);
+
+    // The top wall is only as thick as the walls are, except where other walls join it.
+    let thickness = (0..maze_width)
+        .map(|x| (0..maze_height).take_while(|&y| is_wall(x, y)).count())
+        .filter(|&run| run > 0)
+        .min()
+        .ok_or(ImportError::Grid)?;
+
+    // Every wall starts on a grid line, so the cell size divides the offset of every place a
+    // run of wall starts, as well as the size of the maze without its last wall.
+    let mut cell_size = gcd(
+        maze_width.saturating_sub(thickness),
+        maze_height.saturating_sub(thickness),
+    );
+    for y in 0..maze_height {
+        for x in 1..maze_width {
+            if is_wall(x, y) && !is_wall(x - 1, y) {
+                cell_size = gcd(cell_size, x);
+            }
+        }
+    }
+    for x in 0..maze_width {
+        for y in 1..maze_height {
+            if is_wall(x, y) && !is_wall(x, y - 1) {
+                cell_size = gcd(cell_size, y);
+            }
+        }
+    }
+
+    if cell_size <= thickness {
+        return Err(ImportError::Grid);
+    }
+
+    let width = (maze_width - thickness) / cell_size;
+    let height = (maze_height - thickness) / cell_size;
+
+    // Sample the middle of every wall, away from the corners other walls share.
+    let middle = thickness + (cell_size - thickness) / 2;
+    let mut nodes = vec![vec![Node::new(); height]; width];
+    for (x, column) in nodes.iter_mut().enumerate() {
+        for (y, node) in column.iter_mut().enumerate() {
+            let (cell_x, cell_y) = (x * cell_size, y * cell_size);
+            node.up = y == 0 || is_wall(cell_x + middle, cell_y + thickness / 2);
+            node.left = x == 0 || is_wall(cell_x + thickness / 2, cell_y + middle);
+        }
+    }
+
+    Ok(nodes)
+}
+
+/// Reads a maze from a PNG, see `from_canvas`.
+pub fn read_png<R: Read>(reader: R) -> io::Result<Vec<Vec<Node>>> {
+    let canvas = Canvas::read_png(reader)?;
+    from_canvas(&canvas).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+pub fn load_png<P: AsRef<Path>>(path: P) -> io::Result<Vec<Vec<Node>>> {
+    let canvas = Canvas::load_png(path)?;
+    from_canvas(&canvas).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{formats::walls::wall_segments, helpers::Node, NODE_SIZE};
+
+/// How `extrude` builds a mesh out of a maze, all sizes in model units.
+///
+/// The mesh stands on the XY plane with Z pointing up. The top row of the maze has the largest
+/// Y, so the maze reads the same way as on screen when viewed from above.
+#[derive(Clone, Copy, Debug)]
+pub struct MeshOptions {
+    pub cell_size: f32,
+    pub wall_height: f32,
+    pub wall_thickness: f32,
+    /// Adds a plate of this thickness under the whole maze when set.
+    pub floor_thickness: Option<f32>,
+}
+
+impl Default for MeshOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: NODE_SIZE as f32,
+            wall_height: NODE_SIZE as f32,
+            wall_thickness: 1.0,
+            floor_thickness: Some(1.0),
+        }
+    }
+}
+
+/// A triangle mesh with counterclockwise winding when seen from outside.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<[f32; 3]>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl Mesh {
+    /// Adds an axis aligned box spanning `min` to `max`.
+    fn add_box(&mut self, min: [f32; 3], max: [f32; 3]) {
+        // Corner `i` takes its X from bit 0, its Y from bit 1 and its Z from bit 2.
+        const FACES: [[u32; 4]; 6] = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ];
+
+        let first = self.vertices.len() as u32;
+        for i in 0..8 {
+            let pick = |axis: usize| {
+                if i & (1 << axis) == 0 {
+                    min[axis]
+                } else {
+                    max[axis]
+                }
+            };
+            self.vertices.push([pick(0), pick(1), pick(2)]);
+        }
+
+        for [a, b, c, d] in FACES {
+            self.triangles.push([first + a, first + b, first + c]);
+            self.triangles.push([first + a, first + c, first + d]);
+        }
+    }
+
+    pub fn write_obj<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "# rust_mazes")?;
+        for [x, y, z] in &self.vertices {
+            writeln!(writer, "v {x} {y} {z}")?;
+        }
+        // OBJ indices start at 1.
+        for [a, b, c] in &self.triangles {
+            writeln!(writer, "f {} {} {}", a + 1, b + 1, c + 1)?;
+        }
+        writer.flush()
+    }
+
+    /// Writes the mesh as a binary STL file.
+    pub fn write_stl<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut header = [0; 80];
+        header[..10].copy_from_slice(b"rust_mazes");
+        writer.write_all(&header)?;
+
+        let count = u32::try_from(self.triangles.len()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "STL files can't hold more than u32::MAX triangles",
+            )
+        })?;
+        writer.write_all(&count.to_le_bytes())?;
+
+        for triangle in &self.triangles {
+            let [a, b, c] = triangle.map(|i| self.vertices[i as usize]);
+
+            for value in normal(a, b, c).iter().chain(&a).chain(&b).chain(&c) {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+            // The attribute byte count, which nothing uses.
+            writer.write_all(&[0, 0])?;
+        }
+        writer.flush()
+    }
+
+    pub fn save_obj<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_obj(BufWriter::new(File::create(path)?))
+    }
+
+    pub fn save_stl<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_stl(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Extrudes every wall of `nodes` into a box, with collinear walls merged into one long box.
+/// Boxes overlap where walls meet, which slicers and game engines treat as a union.
+pub fn extrude(nodes: &[Vec<Node>], options: &MeshOptions) -> Mesh {
+    let height = nodes.first().map_or(0, Vec::len);
+
+    let cell = options.cell_size;
+    let half = options.wall_thickness / 2.0;
+    let top = height as f32 * cell;
+
+    let mut mesh = Mesh::default();
+
+    for segment in wall_segments(nodes) {
+        let (from_x, to_x) = (segment.from.x as f32 * cell, segment.to.x as f32 * cell);
+        // Flipping Y keeps the maze the right way round when seen from above.
+        let (from_y, to_y) = (
+            top - segment.to.y as f32 * cell,
+            top - segment.from.y as f32 * cell,
+        );
+
+        mesh.add_box(
+            [from_x - half, from_y - half, 0.0],
+            [to_x + half, to_y + half, options.wall_height],
+        );
+    }
+
+    if let Some(thickness) = options.floor_thickness {
+        let width = nodes.len() as f32 * cell;
+        mesh.add_box([-half, -half, -thickness], [width + half, top + half, 0.0]);
+    }
+
+    mesh
+}
+
+fn normal(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> [f32; 3] {
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let n = [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ];
+
+    let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    if length == 0.0 {
+        return [0.0; 3];
+    }
+    n.map(|value| value / length)
+}
@@ -0,0 +1,21 @@
+mod graph;
+mod import;
+mod mesh;
+mod raster;
+mod recording;
+mod saved;
+mod svg;
+mod text;
+mod tilemap;
+mod walls;
+
+pub use graph::{passage_graph, Edge, PassageGraph};
+pub use import::{from_canvas, load_png, read_png, ImportError};
+pub use mesh::{extrude, Mesh, MeshOptions};
+pub use raster::{rasterize, rasterize_progress, save_png, Canvas, RasterOptions};
+pub use recording::{record_gif, record_png_frames, save_gif, RecordOptions};
+pub use saved::{SavedMaze, Topology, FORMAT_VERSION};
+pub use svg::{save_svg, write_svg, SvgOptions};
+pub use text::{parse_ascii, to_text, ParseError, TextStyle};
+pub use tilemap::{to_tilemap, Tilemap, TilemapOptions, FLOOR_TILE, WALL_TILE};
+pub use walls::{wall_segments, Segment};
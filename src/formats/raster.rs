@@ -0,0 +1,298 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use png::{BitDepth, ColorType, Decoder, Encoder, Transformations};
+
+use crate::{
+    color::Color,
+    helpers::{Node, Pos},
+    maze::Maze,
+    theme::Theme,
+    NODE_SIZE,
+};
+
+/// How `rasterize` lays out a maze, all sizes in pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct RasterOptions {
+    /// The distance between the walls on either side of a cell, measured from the start of
+    /// one wall to the start of the next.
+    pub cell_size: usize,
+    /// Should be smaller than `cell_size`, or the walls cover the passages.
+    pub wall_thickness: usize,
+    /// Empty space around the maze, filled with `background`.
+    pub margin: usize,
+    pub wall: Color,
+    pub passage: Color,
+    pub background: Color,
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: NODE_SIZE,
+            wall_thickness: 1,
+            margin: NODE_SIZE,
+            wall: Color::BLACK,
+            passage: Color::WHITE,
+            background: Color::WHITE,
+        }
+    }
+}
+
+impl RasterOptions {
+    /// Takes the wall, passage and background colors from `theme`.
+    pub fn themed(self, theme: &Theme) -> Self {
+        Self {
+            wall: theme.wall,
+            passage: theme.passage,
+            background: theme.background,
+            ..self
+        }
+    }
+}
+
+/// An RGBA image that can be drawn to without a window.
+#[derive(Clone, Debug)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, color: Color) -> Self {
+        let pixels = [color.r, color.g, color.b, color.a].repeat(width * height);
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The pixels in row-major order, four bytes each.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Color {
+        let i = (y * self.width + x) * 4;
+        Color::new(
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        )
+    }
+
+    /// Fills a rectangle, clipping whatever falls outside the canvas.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Color) {
+        let right = (x + width).min(self.width);
+        let bottom = (y + height).min(self.height);
+
+        for row in y..bottom {
+            for column in x..right {
+                let i = (row * self.width + column) * 4;
+                self.pixels[i..i + 4].copy_from_slice(&[color.r, color.g, color.b, color.a]);
+            }
+        }
+    }
+
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(ColorType::Rgba);
+        encoder.set_depth(BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+
+    /// Decodes a PNG of any color type and bit depth into a canvas.
+    pub fn read_png<R: Read>(reader: R) -> io::Result<Self> {
+        let mut decoder = Decoder::new(reader);
+        decoder.set_transformations(Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        let buffer = &buffer[..info.buffer_size()];
+
+        let pixels = match info.color_type {
+            ColorType::Rgba => buffer.to_vec(),
+            ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            ColorType::Indexed => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "the PNG palette was not expanded",
+                ))
+            }
+        };
+
+        Ok(Self {
+            width: info.width as usize,
+            height: info.height as usize,
+            pixels,
+        })
+    }
+
+    pub fn load_png<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_png(BufReader::new(File::open(path)?))
+    }
+}
+
+/// Draws the walls of `nodes` onto a new canvas.
+///
+/// Every wall is drawn `wall_thickness` pixels wide starting on its grid line, so the canvas
+/// is `width * cell_size + wall_thickness` pixels wide plus the margins, and likewise for its
+/// height.
+pub fn rasterize(nodes: &[Vec<Node>], options: &RasterOptions) -> Canvas {
+    draw_grid(nodes, options, options.wall, |_| true, &[])
+}
+
+/// Draws a maze that may still be generating, the way its `draw` method shows it: cells it
+/// has not reached yet are filled with the theme's background and its highlights are drawn
+/// over the passages in the theme's colors.
+pub fn rasterize_progress(maze: &dyn Maze, options: &RasterOptions, theme: &Theme) -> Canvas {
+    let highlights: Vec<(Pos, Color)> = maze
+        .highlights()
+        .into_iter()
+        .map(|(pos, highlight)| (pos, theme.highlight(highlight)))
+        .collect();
+
+    draw_grid(
+        maze.nodes(),
+        options,
+        theme.background,
+        |pos| maze.is_visited(pos),
+        &highlights,
+    )
+}
+
+fn draw_grid(
+    nodes: &[Vec<Node>],
+    options: &RasterOptions,
+    unvisited: Color,
+    is_visited: impl Fn(Pos) -> bool,
+    highlights: &[(Pos, Color)],
+) -> Canvas {
+    let width = nodes.len();
+    let height = nodes.first().map_or(0, Vec::len);
+
+    let cell = options.cell_size;
+    let thickness = options.wall_thickness;
+    let margin = options.margin;
+
+    let maze_width = width * cell + thickness;
+    let maze_height = height * cell + thickness;
+
+    let mut canvas = Canvas::new(
+        maze_width + 2 * margin,
+        maze_height + 2 * margin,
+        options.background,
+    );
+    canvas.fill_rect(margin, margin, maze_width, maze_height, unvisited);
+
+    let fill_cell = |canvas: &mut Canvas, pos: Pos, color: Color| {
+        canvas.fill_rect(
+            margin + pos.x * cell,
+            margin + pos.y * cell,
+            cell + thickness,
+            cell + thickness,
+            color,
+        );
+    };
+
+    for x in 0..width {
+        for y in 0..height {
+            let pos = Pos::new(x, y);
+            if is_visited(pos) {
+                fill_cell(&mut canvas, pos, options.passage);
+            }
+        }
+    }
+
+    for &(pos, color) in highlights {
+        if pos.x < width && pos.y < height {
+            fill_cell(&mut canvas, pos, color);
+        }
+    }
+
+    for (x, column) in nodes.iter().enumerate() {
+        for (y, node) in column.iter().enumerate() {
+            let screen_x = margin + x * cell;
+            let screen_y = margin + y * cell;
+
+            if node.up {
+                canvas.fill_rect(
+                    screen_x,
+                    screen_y,
+                    cell + thickness,
+                    thickness,
+                    options.wall,
+                );
+            }
+            if node.left {
+                canvas.fill_rect(
+                    screen_x,
+                    screen_y,
+                    thickness,
+                    cell + thickness,
+                    options.wall,
+                );
+            }
+        }
+    }
+
+    // The right and bottom edges are not part of any node.
+    canvas.fill_rect(
+        margin + width * cell,
+        margin,
+        thickness,
+        maze_height,
+        options.wall,
+    );
+    canvas.fill_rect(
+        margin,
+        margin + height * cell,
+        maze_width,
+        thickness,
+        options.wall,
+    );
+
+    canvas
+}
+
+/// Rasterizes `nodes` and saves the result as a PNG.
+pub fn save_png<P: AsRef<Path>>(
+    nodes: &[Vec<Node>],
+    options: &RasterOptions,
+    path: P,
+) -> io::Result<()> {
+    rasterize(nodes, options).save_png(path)
+}
@@ -0,0 +1,170 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use gif::{Encoder, Frame, Repeat};
+
+use crate::{
+    formats::raster::{rasterize_progress, Canvas, RasterOptions},
+    maze::Maze,
+    theme::Theme,
+};
+
+/// How `record_gif` and `record_png_frames` capture a maze being generated.
+#[derive(Clone, Debug)]
+pub struct RecordOptions {
+    /// How many times `Maze::step` runs between frames.
+    pub steps_per_frame: usize,
+    /// How long every frame but the last is shown, in hundredths of a second.
+    pub frame_delay: u16,
+    /// How long the finished maze is shown before the animation starts over, in hundredths of a
+    /// second.
+    pub final_delay: u16,
+    pub raster: RasterOptions,
+    /// Colors the cells that have not been reached yet and the highlights.
+    pub theme: Theme,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        Self {
+            steps_per_frame: 1,
+            frame_delay: 2,
+            final_delay: 200,
+            raster: RasterOptions::default().themed(&Theme::default()),
+            theme: Theme::default(),
+        }
+    }
+}
+
+/// Generates the rest of `maze`, calling `on_frame` with a picture of it before the first step,
+/// after every `steps_per_frame` steps and once it is complete. Returns the number of frames.
+fn capture<F>(maze: &mut dyn Maze, options: &RecordOptions, mut on_frame: F) -> io::Result<usize>
+where
+    F: FnMut(&Canvas, bool) -> io::Result<()>,
+{
+    let mut frames = 0;
+
+    loop {
+        let complete = maze.complete();
+        on_frame(
+            &rasterize_progress(maze, &options.raster, &options.theme),
+            complete,
+        )?;
+        frames += 1;
+
+        if complete {
+            return Ok(frames);
+        }
+
+        for _ in 0..options.steps_per_frame.max(1) {
+            maze.generate();
+        }
+    }
+}
+
+/// Records `maze` generating from its current state until it is complete as a looping GIF.
+/// Returns the number of frames.
+pub fn record_gif<W: Write>(
+    maze: &mut dyn Maze,
+    options: &RecordOptions,
+    writer: W,
+) -> io::Result<usize> {
+    let mut encoder: Option<Encoder<W>> = None;
+    let mut writer = Some(writer);
+
+    capture(maze, options, |canvas, last| {
+        let (width, height) = match (
+            u16::try_from(canvas.width()),
+            u16::try_from(canvas.height()),
+        ) {
+            (Ok(width), Ok(height)) => (width, height),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "GIF frames can't be larger than 65535 pixels on either side",
+                ))
+            }
+        };
+
+        if encoder.is_none() {
+            let writer = writer.take().expect("the writer is only taken once");
+            let mut new_encoder =
+                Encoder::new(writer, width, height, &[]).map_err(io::Error::other)?;
+            new_encoder
+                .set_repeat(Repeat::Infinite)
+                .map_err(io::Error::other)?;
+            encoder = Some(new_encoder);
+        }
+
+        let mut frame = gif_frame(canvas, width, height);
+        frame.delay = if last {
+            options.final_delay
+        } else {
+            options.frame_delay
+        };
+
+        encoder
+            .as_mut()
+            .expect("the encoder was just created")
+            .write_frame(&frame)
+            .map_err(io::Error::other)
+    })
+}
+
+pub fn save_gif<P: AsRef<Path>>(
+    maze: &mut dyn Maze,
+    options: &RecordOptions,
+    path: P,
+) -> io::Result<usize> {
+    record_gif(maze, options, BufWriter::new(File::create(path)?))
+}
+
+/// Records `maze` generating from its current state until it is complete as numbered PNGs,
+/// `frame_00000.png` and onwards, in `directory`, which is created if needed. Returns the
+/// number of frames.
+pub fn record_png_frames<P: AsRef<Path>>(
+    maze: &mut dyn Maze,
+    options: &RecordOptions,
+    directory: P,
+) -> io::Result<usize> {
+    let directory = directory.as_ref();
+    fs::create_dir_all(directory)?;
+
+    let mut index = 0;
+    capture(maze, options, |canvas, _| {
+        canvas.save_png(directory.join(format!("frame_{index:05}.png")))?;
+        index += 1;
+        Ok(())
+    })
+}
+
+/// Converts a canvas to a GIF frame. Mazes only use a handful of colors, so they are indexed
+/// exactly, unless there are too many for a palette and the frame has to be quantized.
+fn gif_frame(canvas: &Canvas, width: u16, height: u16) -> Frame<'static> {
+    let mut palette = vec![];
+    let mut indices: HashMap<&[u8], u8> = HashMap::new();
+    let mut pixels = Vec::with_capacity(canvas.width() * canvas.height());
+
+    for color in canvas.pixels().chunks_exact(4) {
+        let index = match indices.get(color) {
+            Some(&index) => index,
+            None if indices.len() < 256 => {
+                let index = indices.len() as u8;
+                indices.insert(color, index);
+                palette.extend_from_slice(&color[..3]);
+                index
+            }
+            None => {
+                let mut rgba = canvas.pixels().to_vec();
+                return Frame::from_rgba_speed(width, height, &mut rgba, 10);
+            }
+        };
+        pixels.push(index);
+    }
+
+    Frame::from_palette_pixels(width, height, pixels, palette, None)
+}
@@ -0,0 +1,389 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helpers::{Node, Pos},
+    maze::Maze,
+    Algorithm,
+};
+
+/// The newest version of the format `SavedMaze` reads and writes.
+pub const FORMAT_VERSION: u32 = 1;
+
+const MAGIC: &[u8; 4] = b"MAZE";
+const HAS_SEED: u8 = 1;
+const HAS_ALGORITHM: u8 = 1 << 1;
+const HAS_START: u8 = 1 << 2;
+const HAS_GOAL: u8 = 1 << 3;
+
+/// The most cells a loaded maze may have, so a corrupt or hostile header can't make loading
+/// allocate more memory than the machine has.
+pub const MAX_CELLS: usize = 1 << 26;
+
+/// How the cells of a maze are connected to each other.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Topology {
+    /// A grid of square cells, each with its neighbors above, below, left and right.
+    #[default]
+    Rectangular,
+}
+
+/// A maze along with how it was made, which can be saved as JSON or as a compact binary file
+/// and loaded back.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SavedMaze {
+    pub topology: Topology,
+    /// Indexed as `nodes[x][y]`, like the nodes of a `Maze`.
+    pub nodes: Vec<Vec<Node>>,
+    pub seed: Option<u64>,
+    pub algorithm: Option<String>,
+    pub start: Option<Pos>,
+    pub goal: Option<Pos>,
+}
+
+/// The JSON form of a `SavedMaze`. Every row of cells is a string with one digit per cell:
+/// 1 if its up wall stands plus 2 if its left wall stands.
+#[derive(Serialize, Deserialize)]
+struct JsonMaze {
+    version: u32,
+    topology: Topology,
+    width: usize,
+    height: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    algorithm: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start: Option<Pos>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    goal: Option<Pos>,
+    rows: Vec<String>,
+}
+
+impl SavedMaze {
+    pub fn new(nodes: Vec<Vec<Node>>) -> Self {
+        Self {
+            topology: Topology::Rectangular,
+            nodes,
+            seed: None,
+            algorithm: None,
+            start: None,
+            goal: None,
+        }
+    }
+
+    /// Saves the current state of `maze`, along with its seed and the algorithm that made it.
+    pub fn from_maze(maze: &dyn Maze, algorithm: Algorithm) -> Self {
+        Self {
+            seed: Some(maze.seed()),
+            algorithm: Some(algorithm.id().to_string()),
+            ..Self::new(maze.nodes().to_vec())
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.nodes.first().map_or(0, Vec::len)
+    }
+
+    pub fn write_json<W: Write>(&self, writer: W) -> io::Result<()> {
+        let rows = (0..self.height())
+            .map(|y| {
+                self.nodes
+                    .iter()
+                    .map(|column| char::from(b'0' + wall_bits(column[y])))
+                    .collect()
+            })
+            .collect();
+
+        let json = JsonMaze {
+            version: FORMAT_VERSION,
+            topology: self.topology,
+            width: self.width(),
+            height: self.height(),
+            seed: self.seed,
+            algorithm: self.algorithm.clone(),
+            start: self.start,
+            goal: self.goal,
+            rows,
+        };
+
+        serde_json::to_writer_pretty(writer, &json)?;
+        Ok(())
+    }
+
+    pub fn read_json<R: Read>(reader: R) -> io::Result<Self> {
+        let json: JsonMaze = serde_json::from_reader(reader)?;
+        check_version(json.version)?;
+        check_size(json.width, json.height)?;
+
+        if json.rows.len() != json.height {
+            return Err(invalid_data(format!(
+                "expected {} rows, found {}",
+                json.height,
+                json.rows.len()
+            )));
+        }
+
+        let mut nodes = vec![vec![Node::new(); json.height]; json.width];
+        for (y, row) in json.rows.iter().enumerate() {
+            if row.len() != json.width {
+                return Err(invalid_data(format!(
+                    "row {y} has {} cells instead of {}",
+                    row.len(),
+                    json.width
+                )));
+            }
+
+            for (x, digit) in row.chars().enumerate() {
+                let bits = digit
+                    .to_digit(4)
+                    .ok_or_else(|| invalid_data(format!("{digit:?} is not a cell in row {y}")))?;
+                nodes[x][y] = from_wall_bits(bits as u8);
+            }
+        }
+
+        let maze = Self {
+            topology: json.topology,
+            nodes,
+            seed: json.seed,
+            algorithm: json.algorithm,
+            start: json.start,
+            goal: json.goal,
+        };
+        maze.check_positions()?;
+        Ok(maze)
+    }
+
+    /// Writes the maze in a binary form with every wall packed into two bits per cell:
+    ///
+    /// - the magic bytes `MAZE`, then the format version, a byte of flags for which optional
+    ///   fields follow and the topology, all as single bytes
+    /// - the width and height, as little endian `u32`s
+    /// - the seed as a little endian `u64`, if any
+    /// - the length of the algorithm name as a byte followed by the name in UTF-8, if any
+    /// - the start and goal positions as pairs of little endian `u32`s, if any
+    /// - the walls of every cell in row-major order, four cells per byte starting at the
+    ///   lowest bits, with the up wall in the lower bit of each pair
+    pub fn write_binary<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut flags = 0;
+        if self.seed.is_some() {
+            flags |= HAS_SEED;
+        }
+        if self.algorithm.is_some() {
+            flags |= HAS_ALGORITHM;
+        }
+        if self.start.is_some() {
+            flags |= HAS_START;
+        }
+        if self.goal.is_some() {
+            flags |= HAS_GOAL;
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION as u8, flags, self.topology as u8])?;
+        write_u32(&mut writer, self.width())?;
+        write_u32(&mut writer, self.height())?;
+
+        if let Some(seed) = self.seed {
+            writer.write_all(&seed.to_le_bytes())?;
+        }
+        if let Some(algorithm) = &self.algorithm {
+            let len = u8::try_from(algorithm.len())
+                .map_err(|_| invalid_data("the algorithm name is longer than 255 bytes"))?;
+            writer.write_all(&[len])?;
+            writer.write_all(algorithm.as_bytes())?;
+        }
+        for pos in [self.start, self.goal].into_iter().flatten() {
+            write_u32(&mut writer, pos.x)?;
+            write_u32(&mut writer, pos.y)?;
+        }
+
+        let mut walls = vec![0; (self.width() * self.height()).div_ceil(4)];
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let i = y * self.width() + x;
+                walls[i / 4] |= wall_bits(self.nodes[x][y]) << (i % 4 * 2);
+            }
+        }
+        writer.write_all(&walls)
+    }
+
+    pub fn read_binary<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("this is not a binary maze"));
+        }
+
+        let [version, flags, topology] = read_bytes(&mut reader)?;
+        check_version(version.into())?;
+        let topology = match topology {
+            0 => Topology::Rectangular,
+            _ => return Err(invalid_data(format!("unknown topology {topology}"))),
+        };
+
+        let width = read_u32(&mut reader)?;
+        let height = read_u32(&mut reader)?;
+
+        let seed = if flags & HAS_SEED != 0 {
+            Some(u64::from_le_bytes(read_bytes(&mut reader)?))
+        } else {
+            None
+        };
+        let algorithm = if flags & HAS_ALGORITHM != 0 {
+            let [len] = read_bytes(&mut reader)?;
+            let mut name = vec![0; len.into()];
+            reader.read_exact(&mut name)?;
+            Some(String::from_utf8(name).map_err(invalid_data)?)
+        } else {
+            None
+        };
+        let start = if flags & HAS_START != 0 {
+            Some(Pos::new(read_u32(&mut reader)?, read_u32(&mut reader)?))
+        } else {
+            None
+        };
+        let goal = if flags & HAS_GOAL != 0 {
+            Some(Pos::new(read_u32(&mut reader)?, read_u32(&mut reader)?))
+        } else {
+            None
+        };
+
+        let cells = check_size(width, height)?;
+        let mut walls = vec![0; cells.div_ceil(4)];
+        reader.read_exact(&mut walls)?;
+
+        let nodes = (0..width)
+            .map(|x| {
+                (0..height)
+                    .map(|y| {
+                        let i = y * width + x;
+                        from_wall_bits(walls[i / 4] >> (i % 4 * 2))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let maze = Self {
+            topology,
+            nodes,
+            seed,
+            algorithm,
+            start,
+            goal,
+        };
+        maze.check_positions()?;
+        Ok(maze)
+    }
+
+    /// Saves as JSON if `path` ends in `.json`, and in the binary form otherwise.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            self.write_json(&mut writer)?;
+        } else {
+            self.write_binary(&mut writer)?;
+        }
+
+        writer.flush()
+    }
+
+    /// Loads a maze saved in either form, telling them apart by the binary form's magic bytes.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut bytes = vec![];
+        BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+
+        if bytes.starts_with(MAGIC) {
+            Self::read_binary(bytes.as_slice())
+        } else {
+            Self::read_json(bytes.as_slice())
+        }
+    }
+
+    fn check_positions(&self) -> io::Result<()> {
+        for pos in [self.start, self.goal].into_iter().flatten() {
+            if pos.x >= self.width() || pos.y >= self.height() {
+                return Err(invalid_data(format!(
+                    "({}, {}) is outside the maze",
+                    pos.x, pos.y
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn wall_bits(node: Node) -> u8 {
+    u8::from(node.up) | u8::from(node.left) << 1
+}
+
+fn from_wall_bits(bits: u8) -> Node {
+    Node {
+        up: bits & 1 != 0,
+        left: bits & 2 != 0,
+    }
+}
+
+fn check_version(version: u32) -> io::Result<()> {
+    if version == 0 || version > FORMAT_VERSION {
+        return Err(invalid_data(format!(
+            "format version {version} is not supported, the newest is {FORMAT_VERSION}"
+        )));
+    }
+    Ok(())
+}
+
+/// Fails unless a maze of `width` by `height` has at least one cell and at most `MAX_CELLS`,
+/// and returns how many cells it has.
+fn check_size(width: usize, height: usize) -> io::Result<usize> {
+    if width == 0 || height == 0 {
+        return Err(invalid_data(format!(
+            "the maze is {width}x{height}, but it needs at least one cell each way"
+        )));
+    }
+    width
+        .checked_mul(height)
+        .filter(|&cells| cells <= MAX_CELLS)
+        .ok_or_else(|| {
+            invalid_data(format!(
+                "the maze is {width}x{height}, more than the {MAX_CELLS} cells allowed"
+            ))
+        })
+}
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: usize) -> io::Result<()> {
+    let value = u32::try_from(value).map_err(|_| invalid_data("the maze is too large"))?;
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<usize> {
+    Ok(u32::from_le_bytes(read_bytes(reader)?) as usize)
+}
+
+fn read_bytes<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
@@ -0,0 +1,182 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    color::Color,
+    formats::walls::wall_segments,
+    helpers::{Node, Pos},
+    solve::solve,
+    NODE_SIZE,
+};
+
+/// How `write_svg` lays out a maze, all sizes in user units.
+#[derive(Clone, Copy, Debug)]
+pub struct SvgOptions {
+    pub cell_size: f32,
+    pub wall_thickness: f32,
+    /// Empty space around the maze.
+    pub margin: f32,
+    pub wall: Color,
+    /// Fills the whole image when set, otherwise it is left transparent.
+    pub background: Option<Color>,
+    /// The cell the maze is entered at, marked with a circle.
+    pub entrance: Option<Pos>,
+    /// The cell the maze is left at, marked with a square.
+    pub exit: Option<Pos>,
+    /// Draws the path from `entrance` to `exit` when both are set.
+    pub show_solution: bool,
+    pub solution: Color,
+    pub marker: Color,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: NODE_SIZE as f32,
+            wall_thickness: 1.0,
+            margin: NODE_SIZE as f32,
+            wall: Color::BLACK,
+            background: Some(Color::WHITE),
+            entrance: None,
+            exit: None,
+            show_solution: false,
+            solution: Color::RED,
+            marker: Color::BLUE,
+        }
+    }
+}
+
+impl SvgOptions {
+    /// Enters the maze at the top left cell and leaves it at the bottom right one.
+    pub fn with_corners(mut self, nodes: &[Vec<Node>]) -> Self {
+        let width = nodes.len();
+        let height = nodes.first().map_or(0, Vec::len);
+
+        self.entrance = Some(Pos::new(0, 0));
+        self.exit = Some(Pos::new(width.saturating_sub(1), height.saturating_sub(1)));
+        self
+    }
+}
+
+/// Writes `nodes` as an SVG image with one line per straight run of wall.
+pub fn write_svg<W: Write>(
+    nodes: &[Vec<Node>],
+    options: &SvgOptions,
+    mut writer: W,
+) -> io::Result<()> {
+    let width = nodes.len();
+    let height = nodes.first().map_or(0, Vec::len);
+
+    let cell = options.cell_size;
+    let margin = options.margin;
+    let image_width = width as f32 * cell + 2.0 * margin;
+    let image_height = height as f32 * cell + 2.0 * margin;
+
+    let corner = |pos: Pos| (margin + pos.x as f32 * cell, margin + pos.y as f32 * cell);
+    let center = |pos: Pos| {
+        let (x, y) = corner(pos);
+        (x + cell / 2.0, y + cell / 2.0)
+    };
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{image_width}" height="{image_height}" viewBox="0 0 {image_width} {image_height}">"#
+    )?;
+
+    if let Some(background) = options.background {
+        writeln!(
+            writer,
+            r#"  <rect width="100%" height="100%" {}/>"#,
+            paint("fill", background)
+        )?;
+    }
+
+    if options.show_solution {
+        if let Some(path) = options
+            .entrance
+            .zip(options.exit)
+            .and_then(|(entrance, exit)| solve(nodes, entrance, exit))
+        {
+            let points: Vec<String> = path
+                .into_iter()
+                .map(|pos| {
+                    let (x, y) = center(pos);
+                    format!("{x},{y}")
+                })
+                .collect();
+
+            writeln!(
+                writer,
+                r#"  <polyline points="{}" fill="none" {} stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"/>"#,
+                points.join(" "),
+                paint("stroke", options.solution),
+                cell / 4.0
+            )?;
+        }
+    }
+
+    if let Some(entrance) = options.entrance {
+        let (x, y) = center(entrance);
+        writeln!(
+            writer,
+            r#"  <circle cx="{x}" cy="{y}" r="{}" {}/>"#,
+            cell / 3.0,
+            paint("fill", options.marker)
+        )?;
+    }
+
+    if let Some(exit) = options.exit {
+        let (x, y) = center(exit);
+        let size = cell / 1.5;
+        writeln!(
+            writer,
+            r#"  <rect x="{}" y="{}" width="{size}" height="{size}" {}/>"#,
+            x - size / 2.0,
+            y - size / 2.0,
+            paint("fill", options.marker)
+        )?;
+    }
+
+    writeln!(
+        writer,
+        r#"  <g {} stroke-width="{}" stroke-linecap="square">"#,
+        paint("stroke", options.wall),
+        options.wall_thickness
+    )?;
+    for segment in wall_segments(nodes) {
+        let (x1, y1) = corner(segment.from);
+        let (x2, y2) = corner(segment.to);
+        writeln!(
+            writer,
+            r#"    <line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}"/>"#
+        )?;
+    }
+    writeln!(writer, "  </g>")?;
+
+    writeln!(writer, "</svg>")
+}
+
+pub fn save_svg<P: AsRef<Path>>(
+    nodes: &[Vec<Node>],
+    options: &SvgOptions,
+    path: P,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_svg(nodes, options, &mut writer)?;
+    writer.flush()
+}
+
+/// A `fill` or `stroke` attribute for `color`, with an opacity if it is not fully opaque.
+fn paint(attribute: &str, color: Color) -> String {
+    let mut paint = format!(
+        r##"{attribute}="#{:02x}{:02x}{:02x}""##,
+        color.r, color.g, color.b
+    );
+    if color.a != 255 {
+        paint += &format!(r#" {attribute}-opacity="{}""#, color.a as f32 / 255.0);
+    }
+    paint
+}
@@ -0,0 +1,242 @@
+use std::{error::Error, fmt};
+
+use crate::helpers::Node;
+
+/// The characters `to_text` draws walls with.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TextStyle {
+    /// `+`, `-` and `|`, which `parse_ascii` can read back.
+    Ascii,
+    /// Box-drawing characters, with a junction glyph matching the walls at every corner.
+    Unicode,
+}
+
+/// Draws `nodes` as text, with each cell three characters wide and two lines high including
+/// its top and left walls:
+///
+/// ```text
+/// +--+--+
+/// |     |
+/// +--+  +
+/// |     |
+/// +--+--+
+/// ```
+pub fn to_text(nodes: &[Vec<Node>], style: TextStyle) -> String {
+    let width = nodes.len();
+    let height = nodes.first().map_or(0, Vec::len);
+
+    let mut text = String::new();
+
+    for y in 0..=height {
+        for x in 0..=width {
+            text.push(corner(nodes, x, y, style));
+            if x < width {
+                let wall = horizontal_wall(nodes, x, y);
+                let segment = match (style, wall) {
+                    (_, false) => "  ",
+                    (TextStyle::Ascii, true) => "--",
+                    (TextStyle::Unicode, true) => "──",
+                };
+                text.push_str(segment);
+            }
+        }
+        text.push('\n');
+
+        if y < height {
+            for x in 0..=width {
+                let wall = vertical_wall(nodes, x, y);
+                text.push(match (style, wall) {
+                    (_, false) => ' ',
+                    (TextStyle::Ascii, true) => '|',
+                    (TextStyle::Unicode, true) => '│',
+                });
+                if x < width {
+                    text.push_str("  ");
+                }
+            }
+            text.push('\n');
+        }
+    }
+
+    text
+}
+
+/// Whether there is a wall between corners `(x, y)` and `(x + 1, y)`.
+fn horizontal_wall(nodes: &[Vec<Node>], x: usize, y: usize) -> bool {
+    nodes
+        .get(x)
+        .is_some_and(|column| column.get(y).is_none_or(|node| node.up))
+}
+
+/// Whether there is a wall between corners `(x, y)` and `(x, y + 1)`.
+fn vertical_wall(nodes: &[Vec<Node>], x: usize, y: usize) -> bool {
+    let height = nodes.first().map_or(0, Vec::len);
+    y < height && nodes.get(x).is_none_or(|column| column[y].left)
+}
+
+fn corner(nodes: &[Vec<Node>], x: usize, y: usize, style: TextStyle) -> char {
+    if style == TextStyle::Ascii {
+        return '+';
+    }
+
+    let up = y > 0 && vertical_wall(nodes, x, y - 1);
+    let down = vertical_wall(nodes, x, y);
+    let left = x > 0 && horizontal_wall(nodes, x - 1, y);
+    let right = horizontal_wall(nodes, x, y);
+
+    match (up, down, left, right) {
+        (false, false, false, false) => ' ',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╷',
+        (false, false, true, false) => '╴',
+        (false, false, false, true) => '╶',
+        (true, true, false, false) => '│',
+        (false, false, true, true) => '─',
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
+}
+
+/// Why `parse_ascii` could not read a maze. Lines and columns start at 1.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParseError {
+    /// There were no lines besides blank ones.
+    Empty,
+    /// The line does not have the same length as the first one, or there is an even number of
+    /// lines so the last row of cells has no bottom wall.
+    Size { line: usize },
+    /// A character that does not belong at that place in the grid.
+    Unexpected {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// The right or bottom boundary has a gap, which a grid of nodes can't represent.
+    OpenBoundary { line: usize, column: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "there is no maze to parse"),
+            ParseError::Size { line } => write!(f, "line {line} does not fit the grid"),
+            ParseError::Unexpected {
+                line,
+                column,
+                found,
+            } => write!(f, "unexpected {found:?} at line {line}, column {column}"),
+            ParseError::OpenBoundary { line, column } => {
+                write!(f, "the boundary is open at line {line}, column {column}")
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Reads a maze drawn by `to_text` with `TextStyle::Ascii` back into nodes. Blank lines around
+/// the maze and whitespace at the end of lines are ignored.
+pub fn parse_ascii(text: &str) -> Result<Vec<Vec<Node>>, ParseError> {
+    let lines: Vec<(usize, Vec<char>)> = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim_end().chars().collect()))
+        .collect();
+    let start = lines
+        .iter()
+        .position(|(_, line)| !line.is_empty())
+        .ok_or(ParseError::Empty)?;
+    let end = lines
+        .iter()
+        .rposition(|(_, line)| !line.is_empty())
+        .unwrap_or(start);
+    let lines = &lines[start..=end];
+
+    let (first_line, first) = &lines[0];
+    if first.len() < 4 || first.len() % 3 != 1 {
+        return Err(ParseError::Size { line: *first_line });
+    }
+    if lines.len() < 3 || lines.len() % 2 != 1 {
+        let (last_line, _) = lines.last().expect("there is a non-blank line");
+        return Err(ParseError::Size { line: *last_line });
+    }
+
+    let width = (first.len() - 1) / 3;
+    let height = (lines.len() - 1) / 2;
+    let mut nodes = vec![vec![Node::new(); height]; width];
+
+    for (row, (line_number, line)) in lines.iter().enumerate() {
+        if line.len() != first.len() {
+            return Err(ParseError::Size { line: *line_number });
+        }
+
+        let y = row / 2;
+        let is_corner_row = row % 2 == 0;
+
+        for (column, &found) in line.iter().enumerate() {
+            let x = column / 3;
+            let is_corner_column = column % 3 == 0;
+            let unexpected = ParseError::Unexpected {
+                line: *line_number,
+                column: column + 1,
+                found,
+            };
+            let open_boundary = ParseError::OpenBoundary {
+                line: *line_number,
+                column: column + 1,
+            };
+
+            match (is_corner_row, is_corner_column) {
+                (true, true) => {
+                    if found != '+' {
+                        return Err(unexpected);
+                    }
+                }
+                (true, false) => {
+                    let wall = match found {
+                        '-' => true,
+                        ' ' => false,
+                        _ => return Err(unexpected),
+                    };
+                    if y == height || y == 0 {
+                        if !wall {
+                            return Err(open_boundary);
+                        }
+                    } else if column % 3 == 1 {
+                        nodes[x][y].up = wall;
+                    } else if nodes[x][y].up != wall {
+                        return Err(unexpected);
+                    }
+                }
+                (false, true) => {
+                    let wall = match found {
+                        '|' => true,
+                        ' ' => false,
+                        _ => return Err(unexpected),
+                    };
+                    if x == width || x == 0 {
+                        if !wall {
+                            return Err(open_boundary);
+                        }
+                    } else {
+                        nodes[x][y].left = wall;
+                    }
+                }
+                (false, false) => {
+                    if found != ' ' {
+                        return Err(unexpected);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(nodes)
+}
@@ -0,0 +1,214 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::helpers::Node;
+
+/// The tile every passage and cell becomes.
+pub const FLOOR_TILE: u32 = 0;
+/// The tile every wall becomes without autotiling.
+pub const WALL_TILE: u32 = 1;
+
+/// How `to_tilemap` and `Tilemap::write_tmx` lay out a maze's tiles.
+#[derive(Clone, Debug)]
+pub struct TilemapOptions {
+    /// Gives every wall tile its own index depending on which of its four neighbors are walls
+    /// too, so corners, ends and junctions can be drawn differently.
+    ///
+    /// The index is `WALL_TILE` plus a mask of 1 for the wall above, 2 to the right, 4 below and
+    /// 8 to the left, so the tileset needs 17 tiles instead of 2.
+    pub autotile: bool,
+    /// The size of a tile in pixels, which Tiled needs to know.
+    pub tile_size: u32,
+    /// The image the TMX tileset is cut from, with its tiles in a single row in index order.
+    pub tileset_image: Option<String>,
+}
+
+impl Default for TilemapOptions {
+    fn default() -> Self {
+        Self {
+            autotile: false,
+            tile_size: 16,
+            tileset_image: None,
+        }
+    }
+}
+
+/// A maze as a grid of tiles, where a `width` by `height` maze takes `2 * width + 1` by
+/// `2 * height + 1` tiles: every cell, wall and corner gets a tile of its own.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Tilemap {
+    width: usize,
+    height: usize,
+    tiles: Vec<u32>,
+    tile_count: u32,
+}
+
+impl Tilemap {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The tile indices, row by row.
+    pub fn tiles(&self) -> &[u32] {
+        &self.tiles
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u32 {
+        self.tiles[y * self.width + x]
+    }
+
+    /// How many different tiles the tileset needs.
+    pub fn tile_count(&self) -> u32 {
+        self.tile_count
+    }
+
+    /// Writes the tile indices as comma separated rows.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for row in self.tiles.chunks(self.width) {
+            let row: Vec<String> = row.iter().map(u32::to_string).collect();
+            writeln!(writer, "{}", row.join(","))?;
+        }
+        writer.flush()
+    }
+
+    /// Writes a Tiled map with a single tile layer and an embedded tileset.
+    pub fn write_tmx<W: Write>(&self, options: &TilemapOptions, mut writer: W) -> io::Result<()> {
+        let size = options.tile_size;
+
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<map version="1.10" orientation="orthogonal" renderorder="right-down" width="{}" height="{}" tilewidth="{size}" tileheight="{size}" infinite="0" nextlayerid="2" nextobjectid="1">"#,
+            self.width, self.height
+        )?;
+
+        writeln!(
+            writer,
+            r#" <tileset firstgid="1" name="maze" tilewidth="{size}" tileheight="{size}" tilecount="{}" columns="{}">"#,
+            self.tile_count, self.tile_count
+        )?;
+        if let Some(image) = &options.tileset_image {
+            writeln!(
+                writer,
+                r#"  <image source="{}" width="{}" height="{size}"/>"#,
+                escape(image),
+                size * self.tile_count
+            )?;
+        }
+        writeln!(writer, " </tileset>")?;
+
+        writeln!(
+            writer,
+            r#" <layer id="1" name="maze" width="{}" height="{}">"#,
+            self.width, self.height
+        )?;
+        writeln!(writer, r#"  <data encoding="csv">"#)?;
+        let rows = self.tiles.chunks(self.width).count();
+        for (i, row) in self.tiles.chunks(self.width).enumerate() {
+            // Tiled numbers tiles from the tileset's `firstgid`, 0 means no tile.
+            let row: Vec<String> = row.iter().map(|tile| (tile + 1).to_string()).collect();
+            let separator = if i + 1 < rows { "," } else { "" };
+            writeln!(writer, "{}{separator}", row.join(","))?;
+        }
+        writeln!(writer, "  </data>")?;
+        writeln!(writer, " </layer>")?;
+
+        writeln!(writer, "</map>")?;
+        writer.flush()
+    }
+
+    pub fn save_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_csv(BufWriter::new(File::create(path)?))
+    }
+
+    pub fn save_tmx<P: AsRef<Path>>(&self, options: &TilemapOptions, path: P) -> io::Result<()> {
+        self.write_tmx(options, BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Lays `nodes` out on a grid of tiles.
+pub fn to_tilemap(nodes: &[Vec<Node>], options: &TilemapOptions) -> Tilemap {
+    let width = nodes.len();
+    let height = nodes.first().map_or(0, Vec::len);
+
+    let tiles_width = 2 * width + 1;
+    let tiles_height = 2 * height + 1;
+    let mut walls = vec![false; tiles_width * tiles_height];
+
+    for y in 0..=height {
+        for x in 0..=width {
+            // The wall along the top of cell `(x, y)`, which for the last row is the bottom
+            // boundary below it.
+            if x < width {
+                walls[2 * y * tiles_width + 2 * x + 1] = nodes[x].get(y).is_none_or(|node| node.up);
+            }
+            // The wall along the left of cell `(x, y)`.
+            if y < height {
+                walls[(2 * y + 1) * tiles_width + 2 * x] =
+                    nodes.get(x).is_none_or(|column| column[y].left);
+            }
+        }
+    }
+
+    // A corner is solid if any wall meets it. In a perfect maze that is every corner, as four
+    // open walls around one would make a loop.
+    for y in (0..tiles_height).step_by(2) {
+        for x in (0..tiles_width).step_by(2) {
+            let touches_wall = (y > 0 && walls[(y - 1) * tiles_width + x])
+                || (y + 1 < tiles_height && walls[(y + 1) * tiles_width + x])
+                || (x > 0 && walls[y * tiles_width + x - 1])
+                || (x + 1 < tiles_width && walls[y * tiles_width + x + 1]);
+            walls[y * tiles_width + x] = touches_wall;
+        }
+    }
+
+    let is_wall = |x: usize, y: usize| walls[y * tiles_width + x];
+    let mut tiles = Vec::with_capacity(walls.len());
+    for y in 0..tiles_height {
+        for x in 0..tiles_width {
+            let tile = if !is_wall(x, y) {
+                FLOOR_TILE
+            } else if !options.autotile {
+                WALL_TILE
+            } else {
+                let mut mask = 0;
+                if y > 0 && is_wall(x, y - 1) {
+                    mask |= 1;
+                }
+                if x + 1 < tiles_width && is_wall(x + 1, y) {
+                    mask |= 2;
+                }
+                if y + 1 < tiles_height && is_wall(x, y + 1) {
+                    mask |= 4;
+                }
+                if x > 0 && is_wall(x - 1, y) {
+                    mask |= 8;
+                }
+                WALL_TILE + mask
+            };
+            tiles.push(tile);
+        }
+    }
+
+    Tilemap {
+        width: tiles_width,
+        height: tiles_height,
+        tiles,
+        tile_count: if options.autotile { 17 } else { 2 },
+    }
+}
+
+/// Escapes `text` for use in an XML attribute.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,63 @@
+use crate::helpers::{Node, Pos};
+
+/// A straight run of wall between two grid corners, where corner `(x, y)` is the top left
+/// corner of the cell at `(x, y)`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Segment {
+    pub from: Pos,
+    pub to: Pos,
+}
+
+/// Every wall of `nodes`, including the outer boundary, with collinear walls that touch merged
+/// into a single segment. Horizontal segments come first, top to bottom, then vertical ones,
+/// left to right.
+pub fn wall_segments(nodes: &[Vec<Node>]) -> Vec<Segment> {
+    let width = nodes.len();
+    let height = nodes.first().map_or(0, Vec::len);
+
+    let mut segments = vec![];
+
+    for y in 0..=height {
+        // The bottom boundary is below the last row, so it has no nodes of its own.
+        let walls = nodes
+            .iter()
+            .map(|column| column.get(y).is_none_or(|node| node.up));
+        for (from, to) in runs(walls) {
+            segments.push(Segment {
+                from: Pos::new(from, y),
+                to: Pos::new(to, y),
+            });
+        }
+    }
+
+    for x in 0..=width {
+        let walls = (0..height).map(|y| nodes.get(x).is_none_or(|column| column[y].left));
+        for (from, to) in runs(walls) {
+            segments.push(Segment {
+                from: Pos::new(x, from),
+                to: Pos::new(x, to),
+            });
+        }
+    }
+
+    segments
+}
+
+/// The start and end of every run of consecutive `true`s.
+fn runs(walls: impl Iterator<Item = bool>) -> Vec<(usize, usize)> {
+    let mut runs = vec![];
+    let mut start = None;
+
+    for (i, wall) in walls.chain([false]).enumerate() {
+        match (wall, start) {
+            (true, None) => start = Some(i),
+            (false, Some(from)) => {
+                runs.push((from, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    runs
+}
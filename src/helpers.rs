@@ -1,8 +1,9 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use rand::{distributions::Standard, prelude::Distribution, seq::SliceRandom, thread_rng};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 
-use crate::{GRID_HEIGHT, GRID_WIDTH};
+use crate::events::Event;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Direction {
@@ -23,7 +24,7 @@ impl Direction {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct Pos {
     pub x: usize,
     pub y: usize,
@@ -34,13 +35,17 @@ impl Pos {
         Self { x, y }
     }
 
-    pub fn get_neighbors(&self) -> Vec<Direction> {
+    pub fn random<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Self {
+        Pos::new(rng.gen_range(0..width), rng.gen_range(0..height))
+    }
+
+    pub fn get_neighbors(&self, width: usize, height: usize) -> Vec<Direction> {
         let mut neighbors = vec![];
         if self.x > 0 {
             let new_pos = Pos::new(self.x - 1, self.y);
             neighbors.push(Direction::Left(new_pos));
         }
-        if self.x < GRID_WIDTH - 1 {
+        if self.x < width - 1 {
             let new_pos = Pos::new(self.x + 1, self.y);
             neighbors.push(Direction::Right(new_pos));
         }
@@ -48,7 +53,7 @@ impl Pos {
             let new_pos = Pos::new(self.x, self.y - 1);
             neighbors.push(Direction::Up(new_pos));
         }
-        if self.y < GRID_HEIGHT - 1 {
+        if self.y < height - 1 {
             let new_pos = Pos::new(self.x, self.y + 1);
             neighbors.push(Direction::Down(new_pos));
         }
@@ -56,34 +61,71 @@ impl Pos {
         neighbors
     }
 
-    pub fn neighbors_not_in(&self, set: &HashSet<Pos>) -> Vec<Direction> {
-        let mut neighbors = self.get_neighbors();
+    pub fn neighbors_not_in(
+        &self,
+        set: &HashSet<Pos>,
+        width: usize,
+        height: usize,
+    ) -> Vec<Direction> {
+        let mut neighbors = self.get_neighbors(width, height);
         neighbors.retain(|neighbor| !set.contains(&neighbor.get_pos()));
         neighbors
     }
 
-    pub fn neighbors_in(&self, set: &HashSet<Pos>) -> Vec<Direction> {
-        let mut neighbors = self.get_neighbors();
+    pub fn neighbors_in(&self, set: &HashSet<Pos>, width: usize, height: usize) -> Vec<Direction> {
+        let mut neighbors = self.get_neighbors(width, height);
         neighbors.retain(|neighbor| set.contains(&neighbor.get_pos()));
         neighbors
     }
 
-    pub fn get_random_neighbor(&self) -> Option<Direction> {
-        self.get_neighbors().choose(&mut thread_rng()).copied()
+    /// The neighbors that can be reached from this position without crossing a wall.
+    pub fn open_neighbors(&self, nodes: &[Vec<Node>]) -> Vec<Direction> {
+        let width = nodes.len();
+        let height = nodes[0].len();
+
+        let mut neighbors = self.get_neighbors(width, height);
+        neighbors.retain(|neighbor| match neighbor {
+            Direction::Left(_) => !nodes[self.x][self.y].left,
+            Direction::Right(next_pos) => !nodes[next_pos.x][next_pos.y].left,
+            Direction::Up(_) => !nodes[self.x][self.y].up,
+            Direction::Down(next_pos) => !nodes[next_pos.x][next_pos.y].up,
+        });
+        neighbors
+    }
+
+    pub fn get_random_neighbor<R: Rng + ?Sized>(
+        &self,
+        width: usize,
+        height: usize,
+        rng: &mut R,
+    ) -> Option<Direction> {
+        self.get_neighbors(width, height).choose(rng).copied()
     }
 
-    pub fn get_random_neighbor_not_in(&self, set: &HashSet<Pos>) -> Option<Direction> {
-        self.neighbors_not_in(set)
-            .choose(&mut thread_rng())
+    pub fn get_random_neighbor_not_in<R: Rng + ?Sized>(
+        &self,
+        set: &HashSet<Pos>,
+        width: usize,
+        height: usize,
+        rng: &mut R,
+    ) -> Option<Direction> {
+        self.neighbors_not_in(set, width, height)
+            .choose(rng)
             .copied()
     }
 
-    pub fn get_random_neighbor_in(&self, set: &HashSet<Pos>) -> Option<Direction> {
-        self.neighbors_in(set).choose(&mut thread_rng()).copied()
+    pub fn get_random_neighbor_in<R: Rng + ?Sized>(
+        &self,
+        set: &HashSet<Pos>,
+        width: usize,
+        height: usize,
+        rng: &mut R,
+    ) -> Option<Direction> {
+        self.neighbors_in(set, width, height).choose(rng).copied()
     }
 
-    pub fn has_neighbors_in(&self, set: &HashSet<Pos>) -> bool {
-        !self.neighbors_in(set).is_empty()
+    pub fn has_neighbors_in(&self, set: &HashSet<Pos>, width: usize, height: usize) -> bool {
+        !self.neighbors_in(set, width, height).is_empty()
     }
 
     pub fn make_connection(&self, neighbor: &Direction, nodes: &mut [Vec<Node>]) {
@@ -96,13 +138,7 @@ impl Pos {
     }
 }
 
-impl Distribution<Pos> for Standard {
-    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Pos {
-        Pos::new(rng.gen_range(0..GRID_WIDTH), rng.gen_range(0..GRID_HEIGHT))
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Node {
     pub up: bool,
     pub left: bool,
@@ -123,19 +159,68 @@ impl Default for Node {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct KruskalNode {
-    pub up: bool,
-    pub left: bool,
-    pub set: i32,
+/// The cells a generator worked on most recently, newest first, up to a set length. A cell can
+/// be on it more than once.
+#[derive(Clone, Debug)]
+pub struct Trail {
+    cells: VecDeque<Pos>,
+    /// How many times each cell is on the trail, to tell whether it is in constant time.
+    counts: HashMap<Pos, usize>,
+    len: usize,
 }
 
-impl KruskalNode {
-    pub fn new(set: i32) -> Self {
+impl Trail {
+    pub fn new(len: usize) -> Self {
         Self {
-            up: true,
-            left: true,
-            set,
+            cells: VecDeque::new(),
+            counts: HashMap::new(),
+            len,
+        }
+    }
+
+    /// Puts `pos` at the front, dropping the oldest cell once the trail is full.
+    pub fn push(&mut self, pos: Pos, emit: &mut dyn FnMut(Event)) {
+        self.cells.push_front(pos);
+        *self.counts.entry(pos).or_default() += 1;
+        emit(Event::Trailed(pos));
+
+        while self.cells.len() > self.len {
+            let Some(oldest) = self.cells.pop_back() else {
+                break;
+            };
+            self.forget(oldest);
+            emit(Event::Faded(oldest));
+        }
+    }
+
+    /// Empties the trail, reporting every cell that leaves it.
+    pub fn fade(&mut self, emit: &mut dyn FnMut(Event)) {
+        for pos in self.cells.drain(..) {
+            emit(Event::Faded(pos));
+        }
+        self.counts.clear();
+    }
+
+    /// Empties the trail without reporting anything, for a reset.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.counts.clear();
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.counts.contains_key(&pos)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Pos> {
+        self.cells.iter()
+    }
+
+    fn forget(&mut self, pos: Pos) {
+        if let Some(count) = self.counts.get_mut(&pos) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&pos);
+            }
         }
     }
 }
@@ -0,0 +1,29 @@
+pub mod cli;
+pub mod color;
+#[cfg(feature = "viewer")]
+pub mod compare;
+pub mod config;
+pub mod events;
+pub mod formats;
+pub mod helpers;
+pub mod maze;
+pub mod mazes;
+pub mod pace;
+pub mod play;
+pub mod registry;
+pub mod render;
+pub mod solve;
+pub mod stats;
+pub mod theme;
+pub mod timeline;
+pub mod uniformity;
+pub mod validate;
+#[cfg(feature = "viewer")]
+pub mod viewer;
+
+pub use registry::Algorithm;
+
+pub const NODE_SIZE: usize = 10;
+pub const NODE_SIZE_I: i32 = NODE_SIZE as i32;
+pub const GRID_WIDTH: usize = 192;
+pub const GRID_HEIGHT: usize = 102;
@@ -1,96 +1,120 @@
-#![allow(unused)]
-#![windows_subsystem = "windows"]
-
-mod helpers;
-mod maze;
-mod mazes;
-
-use std::time::Instant;
-
-use maze::Maze;
-use mazes::*;
-use rand::{distributions::Standard, prelude::Distribution};
-
-pub const NODE_SIZE: usize = 10;
-pub const NODE_SIZE_I: i32 = NODE_SIZE as i32;
-pub const GRID_WIDTH: usize = 192;
-pub const GRID_HEIGHT: usize = 102;
-
-#[derive(Debug)]
-pub enum Algorithm {
-    DepthFirstSearch,
-    BinaryTree,
-    HuntAndKill,
-    Prim,
-    Kruskal,
-    AldousBroder,
-}
+use std::{error::Error, process};
+
+use clap::Parser;
+use rust_mazes::cli::{self, Cli, Command, CompareArgs, ViewArgs};
+#[cfg(feature = "viewer")]
+use rust_mazes::{compare::Comparison, config, render::Renderer, theme::Theme, viewer::Viewer};
+
+fn main() {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        None => view(&cli.view),
+        Some(Command::View(args)) => view(args),
+        Some(Command::Compare(args)) => compare(args),
+        Some(Command::Generate(args)) => cli::generate(args),
+        Some(Command::Solve(args)) => cli::solve_maze(args),
+        Some(Command::Stats(args)) => cli::stats(args),
+        Some(Command::Bench(args)) => cli::bench(args),
+    };
 
-impl Algorithm {
-    fn next(&self) -> Self {
-        match self {
-            Algorithm::DepthFirstSearch => Algorithm::BinaryTree,
-            Algorithm::BinaryTree => Algorithm::HuntAndKill,
-            Algorithm::HuntAndKill => Algorithm::Prim,
-            Algorithm::Prim => Algorithm::Kruskal,
-            Algorithm::Kruskal => Algorithm::AldousBroder,
-            Algorithm::AldousBroder => Algorithm::DepthFirstSearch,
-        }
+    if let Err(err) = result {
+        eprintln!("{err}");
+        process::exit(1);
     }
 }
 
-impl Distribution<Algorithm> for Standard {
-    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Algorithm {
-        match rng.gen_range(0..6) {
-            0 => Algorithm::DepthFirstSearch,
-            1 => Algorithm::BinaryTree,
-            2 => Algorithm::HuntAndKill,
-            3 => Algorithm::Prim,
-            4 => Algorithm::Kruskal,
-            _ => Algorithm::AldousBroder,
-        }
+#[cfg(feature = "viewer")]
+fn view(args: &ViewArgs) -> Result<(), Box<dyn Error>> {
+    let mut config = args.config()?;
+    config.check()?;
+
+    let current = match args.algorithm {
+        Some(algorithm) => algorithm,
+        None => config.playlist.next(None, &mut rand::thread_rng()),
+    };
+
+    // The configured theme comes first, then the built-in ones, which the viewer cycles
+    // through.
+    let mut themes = Theme::builtin();
+    if let Some(theme) = config.theme()? {
+        themes.retain(|builtin| builtin.name != theme.name);
+        themes.insert(0, theme);
     }
-}
 
-fn main() {
-    let mut depth = DepthFirstSearch::new();
-    let mut binary = BinaryTree::new();
-    let mut hunt = HuntAndKill::new();
-    let mut prim = Prim::new();
-    let mut kruskal = Kruskal::new();
-    let mut aldous = AldousBroder::new();
-
-    depth.reset();
-    binary.reset();
-    hunt.reset();
-    prim.reset();
-    kruskal.reset();
-    aldous.reset();
-
-    let mut current = rand::random();
-
-    let (mut rl, thread) = raylib::init()
+    let renderer = Renderer::new(config.render_options());
+    let options = &renderer.options;
+
+    let mut builder = raylib::init();
+    builder
         .size(
-            (GRID_WIDTH * NODE_SIZE) as i32,
-            (GRID_HEIGHT * NODE_SIZE) as i32,
+            (config.width as f32 * options.cell_size) as i32,
+            (config.height as f32 * options.cell_size) as i32,
         )
-        .title("Maze")
-        .build();
+        .title("Maze");
+    if options.anti_alias {
+        builder.msaa_4x();
+    }
+    let (mut rl, thread) = builder.build();
+
+    let mut viewer = Viewer::new(current, config.width, config.height, renderer, themes);
+    viewer.hold = config::hold(config.hold)?;
+    viewer.playlist = config.playlist.clone();
+    viewer.play = args.play;
+    if let Some(seed) = args.seed {
+        viewer.timeline.reset_with_seed(seed);
+    }
+
+    while !rl.window_should_close() {
+        viewer.update(config.speed(viewer.current), &mut rl, &thread);
+    }
 
-    let mut now = Instant::now();
+    Ok(())
+}
+
+#[cfg(feature = "viewer")]
+fn compare(args: &CompareArgs) -> Result<(), Box<dyn Error>> {
+    let config = args.config()?;
+    config.check()?;
+
+    let mut options = config.render_options();
+    if let Some(theme) = config.theme()? {
+        options.theme = theme;
+    }
+    let anti_alias = options.anti_alias;
 
+    let mut comparison = Comparison::new(&args.algorithms, config.width, config.height, options);
+    comparison.hold = config::hold(config.hold)?;
+
+    let (width, height) = comparison.size();
+    let mut builder = raylib::init();
+    builder.size(width, height).title("Maze comparison");
+    if anti_alias {
+        builder.msaa_4x();
+    }
+    let (mut rl, thread) = builder.build();
+
+    if let Some(seed) = args.seed {
+        comparison.reset_with_seed(seed);
+    }
+
+    let speed = args.speed();
     while !rl.window_should_close() {
-        match current {
-            Algorithm::DepthFirstSearch => {
-                depth.update(&mut now, &mut current, &mut rl, &thread, 110)
-            }
-            Algorithm::BinaryTree => binary.update(&mut now, &mut current, &mut rl, &thread, 25),
-            Algorithm::HuntAndKill => hunt.update(&mut now, &mut current, &mut rl, &thread, 50),
-            Algorithm::Prim => prim.update(&mut now, &mut current, &mut rl, &thread, 30),
-            Algorithm::Kruskal => kruskal.update(&mut now, &mut current, &mut rl, &thread, 120),
-            Algorithm::AldousBroder => {
-                aldous.update(&mut now, &mut current, &mut rl, &thread, 2000)
-            }
-        }
+        comparison.update(speed, &mut rl, &thread);
     }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "viewer"))]
+fn view(_: &ViewArgs) -> Result<(), Box<dyn Error>> {
+    Err(NO_VIEWER.into())
 }
+
+#[cfg(not(feature = "viewer"))]
+fn compare(_: &CompareArgs) -> Result<(), Box<dyn Error>> {
+    Err(NO_VIEWER.into())
+}
+
+#[cfg(not(feature = "viewer"))]
+const NO_VIEWER: &str =
+    "this build has no window to show mazes in, rebuild it with the `viewer` feature";
@@ -1,45 +1,140 @@
-use std::{
-    thread,
-    time::{Duration, Instant},
-};
-
+#[cfg(feature = "viewer")]
 use raylib::prelude::*;
 
-use crate::Algorithm;
+#[cfg(feature = "viewer")]
+use crate::render::{draw_maze, RenderOptions};
+use crate::{
+    events::Event,
+    helpers::{Node, Pos},
+    theme::Highlight,
+    validate::{validate, ValidationError},
+};
 
-pub trait Maze {
-    fn new() -> Self
-    where
-        Self: Sized;
+/// A generator carving a maze one step at a time.
+///
+/// Generators are constructed by their own `new`, usually through the registry in
+/// `crate::registry`, and can be used as `Box<dyn Maze>`.
+pub trait Maze: CloneMaze {
     fn complete(&self) -> bool;
-    fn reset(&mut self);
-    fn generate(&mut self);
-    fn draw(&self, d: &mut RaylibDrawHandle);
-
-    fn update(
-        &mut self,
-        now: &mut Instant,
-        current: &mut Algorithm,
-        rl: &mut RaylibHandle,
-        thread: &RaylibThread,
-        speed: usize,
-    ) {
-        if self.complete() {
-            println!("{current:?} took {:?}", now.elapsed());
-
-            thread::sleep(Duration::from_secs(2));
-            self.reset();
-
-            *current = current.next();
-            *now = Instant::now();
-        }
+    fn reset_with_seed(&mut self, seed: u64);
+    /// Runs one step of the algorithm, passing everything it changed to `emit` in the order
+    /// it happened. Nothing is emitted for the state `reset_with_seed` sets up.
+    fn step(&mut self, emit: &mut dyn FnMut(Event));
+
+    /// The seed passed to the last call of `reset_with_seed`.
+    fn seed(&self) -> u64;
+    /// The grid being carved, indexed as `nodes[x][y]`.
+    fn nodes(&self) -> &[Vec<Node>];
+    /// Whether the cell has been reached yet, so it is drawn as a passage.
+    fn is_visited(&self, pos: Pos) -> bool;
+    /// Cells colored by the theme to show what the algorithm is working on, drawn over the
+    /// passages.
+    fn highlights(&self) -> Vec<(Pos, Highlight)>;
+
+    /// How `highlights` colors the cell, if at all. Generators answer this without going
+    /// through all of their highlights, as it is asked for every cell a step changed.
+    fn highlight(&self, pos: Pos) -> Option<Highlight> {
+        self.highlights()
+            .into_iter()
+            .find(|&(highlighted, _)| highlighted == pos)
+            .map(|(_, highlight)| highlight)
+    }
+
+    fn generate(&mut self) {
+        self.step(&mut |_| {});
+    }
+
+    /// Runs one step and returns what it changed.
+    fn step_events(&mut self) -> Vec<Event> {
+        let mut events = vec![];
+        self.step(&mut |event| events.push(event));
+        events
+    }
 
-        let mut d = rl.begin_drawing(thread);
+    fn reset(&mut self) {
+        self.reset_with_seed(rand::random());
+    }
 
-        for _ in 0..speed {
+    /// Generates the rest of the maze in one go.
+    fn finish(&mut self) {
+        while !self.complete() {
             self.generate();
         }
+    }
+
+    #[cfg(feature = "viewer")]
+    fn draw(&self, d: &mut RaylibDrawHandle, options: &RenderOptions) {
+        draw_maze(d, self, options);
+    }
+
+    /// How many cells `is_visited` counts so far, out of every cell in `nodes`. Generators
+    /// keep count as they go instead of checking every cell.
+    fn visited_cells(&self) -> usize {
+        let nodes = self.nodes();
+        nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(x, column)| (0..column.len()).map(move |y| Pos::new(x, y)))
+            .filter(|&pos| self.is_visited(pos))
+            .count()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate(self.nodes())
+    }
+}
+
+/// Lets boxed mazes be cloned. Every `Maze` that is `Clone` gets it for free.
+pub trait CloneMaze {
+    fn clone_box(&self) -> Box<dyn Maze>;
+}
+
+impl<M: Maze + Clone + 'static> CloneMaze for M {
+    fn clone_box(&self) -> Box<dyn Maze> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Maze> {
+    fn clone(&self) -> Self {
+        self.as_ref().clone_box()
+    }
+}
+
+impl Maze for Box<dyn Maze> {
+    fn complete(&self) -> bool {
+        self.as_ref().complete()
+    }
+
+    fn reset_with_seed(&mut self, seed: u64) {
+        self.as_mut().reset_with_seed(seed);
+    }
+
+    fn step(&mut self, emit: &mut dyn FnMut(Event)) {
+        self.as_mut().step(emit);
+    }
+
+    fn seed(&self) -> u64 {
+        self.as_ref().seed()
+    }
+
+    fn nodes(&self) -> &[Vec<Node>] {
+        self.as_ref().nodes()
+    }
+
+    fn is_visited(&self, pos: Pos) -> bool {
+        self.as_ref().is_visited(pos)
+    }
+
+    fn highlights(&self) -> Vec<(Pos, Highlight)> {
+        self.as_ref().highlights()
+    }
+
+    fn highlight(&self, pos: Pos) -> Option<Highlight> {
+        self.as_ref().highlight(pos)
+    }
 
-        self.draw(&mut d);
+    fn visited_cells(&self) -> usize {
+        self.as_ref().visited_cells()
     }
 }
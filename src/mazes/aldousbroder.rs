@@ -1,117 +1,125 @@
 use std::collections::HashSet;
 
-use raylib::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
 
-use crate::{helpers::*, maze::Maze, GRID_HEIGHT, GRID_WIDTH, NODE_SIZE_I};
+use crate::{events::Event, helpers::*, maze::Maze, theme::Highlight};
 
-const LAST_POSSES_LEN: usize = 5000;
+const TRAIL_LEN: usize = 5000;
 
 #[derive(Clone)]
 pub struct AldousBroder {
+    width: usize,
+    height: usize,
+    seed: u64,
+    rng: StdRng,
     nodes: Vec<Vec<Node>>,
     visited: HashSet<Pos>,
     current_pos: Pos,
-    last_posses: Vec<Pos>,
+    trail: Trail,
 }
 
-impl Maze for AldousBroder {
-    fn new() -> Self {
+impl AldousBroder {
+    pub fn new(width: usize, height: usize) -> Self {
         Self {
+            width,
+            height,
+            seed: 0,
+            rng: StdRng::seed_from_u64(0),
             nodes: vec![],
             visited: HashSet::new(),
             current_pos: Pos::new(0, 0),
-            last_posses: vec![],
+            trail: Trail::new(TRAIL_LEN),
         }
     }
+}
 
+impl Maze for AldousBroder {
     fn complete(&self) -> bool {
-        self.visited.len() == GRID_WIDTH * GRID_HEIGHT
+        self.visited.len() == self.width * self.height
     }
 
-    fn reset(&mut self) {
+    fn reset_with_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+
         self.nodes.clear();
-        for x in 0..GRID_WIDTH {
+        for x in 0..self.width {
             self.nodes.push(vec![]);
-            for _y in 0..GRID_HEIGHT {
+            for _y in 0..self.height {
                 self.nodes[x].push(Node::new());
             }
         }
 
         self.visited.clear();
 
-        let start_pos = rand::random();
+        let start_pos = Pos::random(self.width, self.height, &mut self.rng);
 
         self.visited.insert(start_pos);
         self.current_pos = start_pos;
 
-        self.last_posses.clear();
+        self.trail.clear();
     }
 
-    fn generate(&mut self) {
+    fn step(&mut self, emit: &mut dyn FnMut(Event)) {
         if !self.complete() {
-            if let Some(neighbor) = self.current_pos.get_random_neighbor_not_in(&HashSet::new()) {
+            if let Some(neighbor) =
+                self.current_pos
+                    .get_random_neighbor(self.width, self.height, &mut self.rng)
+            {
                 let neighbor_pos = neighbor.get_pos();
-                if !self.visited.contains(&neighbor_pos) {
+                let first_visit = !self.visited.contains(&neighbor_pos);
+                if first_visit {
                     self.current_pos.make_connection(&neighbor, &mut self.nodes);
+                    emit(Event::Carved {
+                        from: self.current_pos,
+                        to: neighbor_pos,
+                    });
                 }
 
                 self.current_pos = neighbor_pos;
-                self.visited.insert(neighbor_pos);
+                emit(Event::Moved(neighbor_pos));
+                if first_visit {
+                    self.visited.insert(neighbor_pos);
+                    emit(Event::Visited(neighbor_pos));
+                }
 
-                self.last_posses.insert(0, neighbor_pos);
-                self.last_posses.truncate(LAST_POSSES_LEN);
+                self.trail.push(neighbor_pos, emit);
             }
-        } else {
-            self.last_posses.clear();
-        }
-    }
 
-    fn draw(&self, d: &mut RaylibDrawHandle) {
-        d.clear_background(Color::BLACK);
-
-        for (x, row) in self.nodes.iter().enumerate() {
-            for y in 0..row.len() {
-                let node = &self.nodes[x][y];
-                let pos = Pos::new(x, y);
-
-                let screen_x = x as i32 * NODE_SIZE_I;
-                let screen_y = y as i32 * NODE_SIZE_I;
-
-                if self.visited.contains(&pos) {
-                    d.draw_rectangle(screen_x, screen_y, NODE_SIZE_I, NODE_SIZE_I, Color::WHITE);
-
-                    if node.up {
-                        d.draw_line(
-                            screen_x,
-                            screen_y,
-                            screen_x + NODE_SIZE_I,
-                            screen_y,
-                            Color::BLACK,
-                        );
-                    }
-                    if node.left {
-                        d.draw_line(
-                            screen_x,
-                            screen_y,
-                            screen_x,
-                            screen_y + NODE_SIZE_I,
-                            Color::BLACK,
-                        );
-                    }
-                }
+            if self.complete() {
+                self.trail.fade(emit);
             }
         }
+    }
 
-        if !self.complete() {
-            for pos in &self.last_posses {
-                d.draw_rectangle(
-                    pos.x as i32 * NODE_SIZE_I,
-                    pos.y as i32 * NODE_SIZE_I,
-                    NODE_SIZE_I,
-                    NODE_SIZE_I,
-                    Color::SKYBLUE,
-                );
-            }
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn nodes(&self) -> &[Vec<Node>] {
+        &self.nodes
+    }
+
+    fn is_visited(&self, pos: Pos) -> bool {
+        self.visited.contains(&pos)
+    }
+
+    fn highlights(&self) -> Vec<(Pos, Highlight)> {
+        if self.complete() {
+            return vec![];
         }
+
+        self.trail
+            .iter()
+            .map(|&pos| (pos, Highlight::Trail))
+            .collect()
+    }
+
+    fn highlight(&self, pos: Pos) -> Option<Highlight> {
+        (!self.complete() && self.trail.contains(pos)).then_some(Highlight::Trail)
+    }
+
+    fn visited_cells(&self) -> usize {
+        self.visited.len()
     }
 }
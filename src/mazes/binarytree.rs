@@ -1,30 +1,43 @@
-use rand::random;
-use raylib::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::{helpers::*, maze::Maze, GRID_HEIGHT, GRID_WIDTH, NODE_SIZE_I};
+use crate::{events::Event, helpers::*, maze::Maze, theme::Highlight};
 
+#[derive(Clone)]
 pub struct BinaryTree {
+    width: usize,
+    height: usize,
+    seed: u64,
+    rng: StdRng,
     nodes: Vec<Vec<Node>>,
     current_pos: Pos,
 }
 
-impl Maze for BinaryTree {
-    fn new() -> Self {
+impl BinaryTree {
+    pub fn new(width: usize, height: usize) -> Self {
         Self {
+            width,
+            height,
+            seed: 0,
+            rng: StdRng::seed_from_u64(0),
             nodes: vec![],
             current_pos: Pos::new(0, 0),
         }
     }
+}
 
+impl Maze for BinaryTree {
     fn complete(&self) -> bool {
-        self.current_pos.y == GRID_HEIGHT
+        self.current_pos.y == self.height
     }
 
-    fn reset(&mut self) {
+    fn reset_with_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+
         self.nodes.clear();
-        for x in 0..GRID_WIDTH {
+        for x in 0..self.width {
             self.nodes.push(vec![]);
-            for _y in 0..GRID_HEIGHT {
+            for _y in 0..self.height {
                 self.nodes[x].push(Node::new());
             }
         }
@@ -32,74 +45,80 @@ impl Maze for BinaryTree {
         self.current_pos = Pos::new(0, 0);
     }
 
-    fn generate(&mut self) {
+    fn step(&mut self, emit: &mut dyn FnMut(Event)) {
         if !self.complete() {
-            if self.current_pos.x > 0 && self.current_pos.y > 0 {
-                if random() {
-                    self.nodes[self.current_pos.x][self.current_pos.y].up = false;
+            let pos = self.current_pos;
+            let up = Pos::new(pos.x, pos.y.wrapping_sub(1));
+            let left = Pos::new(pos.x.wrapping_sub(1), pos.y);
+
+            if pos.x > 0 && pos.y > 0 {
+                if self.rng.gen() {
+                    self.nodes[pos.x][pos.y].up = false;
+                    emit(Event::Carved { from: pos, to: up });
                 } else {
-                    self.nodes[self.current_pos.x][self.current_pos.y].left = false;
+                    self.nodes[pos.x][pos.y].left = false;
+                    emit(Event::Carved {
+                        from: pos,
+                        to: left,
+                    });
                 }
-            } else if self.current_pos.x > 0 {
-                self.nodes[self.current_pos.x][self.current_pos.y].left = false;
-            } else if self.current_pos.y > 0 {
-                self.nodes[self.current_pos.x][self.current_pos.y].up = false;
+            } else if pos.x > 0 {
+                self.nodes[pos.x][pos.y].left = false;
+                emit(Event::Carved {
+                    from: pos,
+                    to: left,
+                });
+            } else if pos.y > 0 {
+                self.nodes[pos.x][pos.y].up = false;
+                emit(Event::Carved { from: pos, to: up });
             }
 
             self.current_pos.x += 1;
-            if self.current_pos.x >= GRID_WIDTH {
+            if self.current_pos.x >= self.width {
+                // The finished row is no longer highlighted.
+                for x in 0..self.width {
+                    emit(Event::Faded(Pos::new(x, pos.y)));
+                }
                 self.current_pos.y += 1;
                 self.current_pos.x = 0;
             }
+
+            if !self.complete() {
+                emit(Event::Moved(self.current_pos));
+                emit(Event::Visited(self.current_pos));
+            }
         }
     }
 
-    fn draw(&self, d: &mut RaylibDrawHandle) {
-        d.clear_background(Color::BLACK);
-
-        for x in 0..GRID_WIDTH {
-            for y in 0..GRID_HEIGHT {
-                if (y > self.current_pos.y) || (y == self.current_pos.y && x > self.current_pos.x) {
-                    break;
-                }
+    fn seed(&self) -> u64 {
+        self.seed
+    }
 
-                let node = &self.nodes[x][y];
+    fn nodes(&self) -> &[Vec<Node>] {
+        &self.nodes
+    }
 
-                let screen_x = x as i32 * NODE_SIZE_I;
-                let screen_y = y as i32 * NODE_SIZE_I;
+    fn is_visited(&self, pos: Pos) -> bool {
+        pos.y < self.current_pos.y || (pos.y == self.current_pos.y && pos.x <= self.current_pos.x)
+    }
 
-                if y == self.current_pos.y {
-                    d.draw_rectangle(screen_x, screen_y, NODE_SIZE_I, NODE_SIZE_I, Color::BLUE);
-                } else {
-                    d.draw_rectangle(screen_x, screen_y, NODE_SIZE_I, NODE_SIZE_I, Color::WHITE);
-                }
-                if node.up {
-                    d.draw_line(
-                        screen_x,
-                        screen_y,
-                        screen_x + NODE_SIZE_I,
-                        screen_y,
-                        Color::BLACK,
-                    );
-                }
-                if node.left {
-                    d.draw_line(
-                        screen_x,
-                        screen_y,
-                        screen_x,
-                        screen_y + NODE_SIZE_I,
-                        Color::BLACK,
-                    );
-                }
-            }
+    fn highlights(&self) -> Vec<(Pos, Highlight)> {
+        if self.complete() {
+            return vec![];
         }
 
-        d.draw_rectangle(
-            self.current_pos.x as i32 * NODE_SIZE_I,
-            self.current_pos.y as i32 * NODE_SIZE_I,
-            NODE_SIZE_I,
-            NODE_SIZE_I,
-            Color::BLUE,
-        );
+        (0..=self.current_pos.x)
+            .map(|x| (Pos::new(x, self.current_pos.y), Highlight::Active))
+            .collect()
+    }
+
+    fn highlight(&self, pos: Pos) -> Option<Highlight> {
+        let current = self.current_pos;
+        (pos.y == current.y && pos.x <= current.x).then_some(Highlight::Active)
+    }
+
+    fn visited_cells(&self) -> usize {
+        let current = self.current_pos;
+        (current.y * self.width + current.x + 1).min(self.width * self.height)
     }
 }
@@ -1,117 +1,125 @@
 use std::collections::HashSet;
 
-use raylib::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
 
-use crate::{helpers::*, maze::Maze, GRID_HEIGHT, GRID_WIDTH, NODE_SIZE_I};
+use crate::{events::Event, helpers::*, maze::Maze, theme::Highlight};
 
 #[derive(Clone)]
 pub struct DepthFirstSearch {
+    width: usize,
+    height: usize,
+    seed: u64,
+    rng: StdRng,
     nodes: Vec<Vec<Node>>,
     visited: HashSet<Pos>,
     stack: Vec<Pos>,
+    /// The cells on `stack`, which holds each cell at most once.
+    stacked: HashSet<Pos>,
 }
 
 impl DepthFirstSearch {
-    fn handle_neighbor(&mut self, pos: Pos, neighbor: Direction) {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            seed: 0,
+            rng: StdRng::seed_from_u64(0),
+            nodes: vec![],
+            visited: HashSet::new(),
+            stack: vec![],
+            stacked: HashSet::new(),
+        }
+    }
+
+    fn handle_neighbor(&mut self, pos: Pos, neighbor: Direction, emit: &mut dyn FnMut(Event)) {
         let next_pos = neighbor.get_pos();
 
         self.visited.insert(next_pos);
+        emit(Event::Visited(next_pos));
 
         self.stack.push(pos);
         self.stack.push(next_pos);
+        self.stacked.insert(pos);
+        self.stacked.insert(next_pos);
+        emit(Event::Pushed(pos));
+        emit(Event::Pushed(next_pos));
     }
 }
 
 impl Maze for DepthFirstSearch {
-    fn new() -> Self {
-        Self {
-            nodes: vec![],
-            visited: HashSet::new(),
-            stack: vec![],
-        }
-    }
-
     fn complete(&self) -> bool {
         self.stack.is_empty()
     }
 
-    fn reset(&mut self) {
+    fn reset_with_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+
         self.nodes.clear();
-        for x in 0..GRID_WIDTH {
+        for x in 0..self.width {
             self.nodes.push(vec![]);
-            for _y in 0..GRID_HEIGHT {
+            for _y in 0..self.height {
                 self.nodes[x].push(Node::new());
             }
         }
 
         self.stack.clear();
+        self.stacked.clear();
         self.visited.clear();
 
-        let start_pos = rand::random();
+        let start_pos = Pos::random(self.width, self.height, &mut self.rng);
 
         self.stack.push(start_pos);
+        self.stacked.insert(start_pos);
         self.visited.insert(start_pos);
     }
 
-    fn generate(&mut self) {
+    fn step(&mut self, emit: &mut dyn FnMut(Event)) {
         if let Some(pos) = self.stack.pop() {
-            let neighbor = pos.get_random_neighbor_not_in(&self.visited);
+            self.stacked.remove(&pos);
+            emit(Event::Popped(pos));
+
+            let neighbor = pos.get_random_neighbor_not_in(
+                &self.visited,
+                self.width,
+                self.height,
+                &mut self.rng,
+            );
             if let Some(neighbor) = neighbor {
                 pos.make_connection(&neighbor, &mut self.nodes);
-                self.handle_neighbor(pos, neighbor);
+                emit(Event::Carved {
+                    from: pos,
+                    to: neighbor.get_pos(),
+                });
+                self.handle_neighbor(pos, neighbor, emit);
             }
         }
     }
 
-    fn draw(&self, d: &mut RaylibDrawHandle) {
-        d.clear_background(Color::BLACK);
-
-        for (x, row) in self.nodes.iter().enumerate() {
-            for y in 0..row.len() {
-                let node = &self.nodes[x][y];
-                let pos = Pos::new(x, y);
-
-                let screen_x = x as i32 * NODE_SIZE_I;
-                let screen_y = y as i32 * NODE_SIZE_I;
-
-                if self.visited.contains(&pos) {
-                    if self.stack.contains(&pos) {
-                        d.draw_rectangle(
-                            screen_x,
-                            screen_y,
-                            NODE_SIZE_I,
-                            NODE_SIZE_I,
-                            Color::GREEN,
-                        );
-                    } else {
-                        d.draw_rectangle(
-                            screen_x,
-                            screen_y,
-                            NODE_SIZE_I,
-                            NODE_SIZE_I,
-                            Color::WHITE,
-                        );
-                    }
-                    if node.up {
-                        d.draw_line(
-                            screen_x,
-                            screen_y,
-                            screen_x + NODE_SIZE_I,
-                            screen_y,
-                            Color::BLACK,
-                        );
-                    }
-                    if node.left {
-                        d.draw_line(
-                            screen_x,
-                            screen_y,
-                            screen_x,
-                            screen_y + NODE_SIZE_I,
-                            Color::BLACK,
-                        );
-                    }
-                }
-            }
-        }
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn nodes(&self) -> &[Vec<Node>] {
+        &self.nodes
+    }
+
+    fn is_visited(&self, pos: Pos) -> bool {
+        self.visited.contains(&pos)
+    }
+
+    fn highlights(&self) -> Vec<(Pos, Highlight)> {
+        self.stack
+            .iter()
+            .map(|&pos| (pos, Highlight::Active))
+            .collect()
+    }
+
+    fn highlight(&self, pos: Pos) -> Option<Highlight> {
+        self.stacked.contains(&pos).then_some(Highlight::Active)
+    }
+
+    fn visited_cells(&self) -> usize {
+        self.visited.len()
     }
 }
@@ -1,11 +1,15 @@
 use std::collections::HashSet;
 
-use raylib::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
 
-use crate::{helpers::*, maze::Maze, GRID_HEIGHT, GRID_WIDTH, NODE_SIZE_I};
+use crate::{events::Event, helpers::*, maze::Maze, theme::Highlight};
 
 #[derive(Clone)]
 pub struct HuntAndKill {
+    width: usize,
+    height: usize,
+    seed: u64,
+    rng: StdRng,
     nodes: Vec<Vec<Node>>,
     visited: HashSet<Pos>,
     hunting_pos: Pos,
@@ -14,18 +18,12 @@ pub struct HuntAndKill {
 }
 
 impl HuntAndKill {
-    fn handle_neighbor(&mut self, neighbor: Direction) {
-        let next_pos = neighbor.get_pos();
-
-        self.visited.insert(next_pos);
-
-        self.killing_pos = next_pos;
-    }
-}
-
-impl Maze for HuntAndKill {
-    fn new() -> Self {
+    pub fn new(width: usize, height: usize) -> Self {
         Self {
+            width,
+            height,
+            seed: 0,
+            rng: StdRng::seed_from_u64(0),
             nodes: vec![],
             visited: HashSet::new(),
             hunting_pos: Pos::new(0, 0),
@@ -34,15 +32,35 @@ impl Maze for HuntAndKill {
         }
     }
 
+    /// Whether the hunt hasn't scanned `pos` yet.
+    fn ahead_of_hunt(&self, pos: Pos) -> bool {
+        pos.y > self.hunting_pos.y || (pos.y == self.hunting_pos.y && pos.x > self.hunting_pos.x)
+    }
+
+    fn handle_neighbor(&mut self, neighbor: Direction, emit: &mut dyn FnMut(Event)) {
+        let next_pos = neighbor.get_pos();
+
+        self.visited.insert(next_pos);
+        emit(Event::Visited(next_pos));
+
+        self.killing_pos = next_pos;
+        emit(Event::Moved(next_pos));
+    }
+}
+
+impl Maze for HuntAndKill {
     fn complete(&self) -> bool {
-        self.hunting_pos.y == GRID_HEIGHT
+        self.hunting_pos.y == self.height
     }
 
-    fn reset(&mut self) {
+    fn reset_with_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+
         self.nodes.clear();
-        for x in 0..GRID_WIDTH {
+        for x in 0..self.width {
             self.nodes.push(vec![]);
-            for _y in 0..GRID_HEIGHT {
+            for _y in 0..self.height {
                 self.nodes[x].push(Node::new());
             }
         }
@@ -58,25 +76,43 @@ impl Maze for HuntAndKill {
         self.hunting = true;
     }
 
-    fn generate(&mut self) {
+    fn step(&mut self, emit: &mut dyn FnMut(Event)) {
         if !self.complete() {
             if self.hunting {
-                let neighbor = self.hunting_pos.get_random_neighbor_not_in(&self.visited);
+                let neighbor = self.hunting_pos.get_random_neighbor_not_in(
+                    &self.visited,
+                    self.width,
+                    self.height,
+                    &mut self.rng,
+                );
                 if neighbor.is_some() {
                     self.killing_pos = self.hunting_pos;
                     self.hunting = false;
+                    emit(Event::Moved(self.killing_pos));
                 } else {
                     self.hunting_pos.x += 1;
-                    if self.hunting_pos.x >= GRID_WIDTH {
+                    if self.hunting_pos.x >= self.width {
                         self.hunting_pos.y += 1;
                         self.hunting_pos.x = 0;
                     }
+                    if !self.complete() {
+                        emit(Event::Moved(self.hunting_pos));
+                    }
                 }
             } else {
-                let neighbor = self.killing_pos.get_random_neighbor_not_in(&self.visited);
+                let neighbor = self.killing_pos.get_random_neighbor_not_in(
+                    &self.visited,
+                    self.width,
+                    self.height,
+                    &mut self.rng,
+                );
                 if let Some(neighbor) = neighbor {
                     self.killing_pos.make_connection(&neighbor, &mut self.nodes);
-                    self.handle_neighbor(neighbor);
+                    emit(Event::Carved {
+                        from: self.killing_pos,
+                        to: neighbor.get_pos(),
+                    });
+                    self.handle_neighbor(neighbor, emit);
                 } else {
                     self.hunting = true;
                 }
@@ -84,51 +120,31 @@ impl Maze for HuntAndKill {
         }
     }
 
-    fn draw(&self, d: &mut RaylibDrawHandle) {
-        d.clear_background(Color::BLACK);
-
-        for (x, row) in self.nodes.iter().enumerate() {
-            for y in 0..row.len() {
-                let node = &self.nodes[x][y];
-                let pos = Pos::new(x, y);
-
-                let screen_x = x as i32 * NODE_SIZE_I;
-                let screen_y = y as i32 * NODE_SIZE_I;
-
-                if self.visited.contains(&pos) {
-                    if pos.y > self.hunting_pos.y
-                        || (pos.y == self.hunting_pos.y && pos.x > self.hunting_pos.x)
-                    {
-                        d.draw_rectangle(screen_x, screen_y, NODE_SIZE_I, NODE_SIZE_I, Color::GOLD);
-                    } else {
-                        d.draw_rectangle(
-                            screen_x,
-                            screen_y,
-                            NODE_SIZE_I,
-                            NODE_SIZE_I,
-                            Color::WHITE,
-                        );
-                    }
-                    if node.up {
-                        d.draw_line(
-                            screen_x,
-                            screen_y,
-                            screen_x + NODE_SIZE_I,
-                            screen_y,
-                            Color::BLACK,
-                        );
-                    }
-                    if node.left {
-                        d.draw_line(
-                            screen_x,
-                            screen_y,
-                            screen_x,
-                            screen_y + NODE_SIZE_I,
-                            Color::BLACK,
-                        );
-                    }
-                }
-            }
-        }
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn nodes(&self) -> &[Vec<Node>] {
+        &self.nodes
+    }
+
+    fn is_visited(&self, pos: Pos) -> bool {
+        self.visited.contains(&pos)
+    }
+
+    fn highlights(&self) -> Vec<(Pos, Highlight)> {
+        self.visited
+            .iter()
+            .filter(|&&pos| self.ahead_of_hunt(pos))
+            .map(|&pos| (pos, Highlight::Frontier))
+            .collect()
+    }
+
+    fn highlight(&self, pos: Pos) -> Option<Highlight> {
+        (self.ahead_of_hunt(pos) && self.visited.contains(&pos)).then_some(Highlight::Frontier)
+    }
+
+    fn visited_cells(&self) -> usize {
+        self.visited.len()
     }
 }
@@ -1,180 +1,187 @@
 use std::collections::HashSet;
 
-use rand::{seq::SliceRandom, thread_rng};
-use raylib::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 
-use crate::{helpers::*, maze::Maze, GRID_HEIGHT, GRID_WIDTH, NODE_SIZE_I};
+use crate::{events::Event, helpers::*, maze::Maze, theme::Highlight};
 
-const LAST_POSSES_LEN: usize = 1100;
+const TRAIL_LEN: usize = 1100;
 
 #[derive(Clone)]
 pub struct Kruskal {
-    nodes: Vec<Vec<KruskalNode>>,
+    width: usize,
+    height: usize,
+    seed: u64,
+    rng: StdRng,
+    nodes: Vec<Vec<Node>>,
+    /// A union-find forest over the cells, numbered `x * height + y`. Each set of connected
+    /// cells is a tree, named after the cell at its root.
+    parents: Vec<usize>,
+    /// An upper bound on the height of the tree under each root.
+    ranks: Vec<u8>,
     visited: HashSet<Pos>,
     unvisited: Vec<Pos>,
     all: Vec<Pos>,
     num_sets: usize,
-    last_posses: Vec<Pos>,
+    trail: Trail,
 }
 
 impl Kruskal {
-    fn handle_position(&mut self, pos: Pos) {
-        let neighbor = pos.get_random_neighbor_not_in(&HashSet::new());
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            seed: 0,
+            rng: StdRng::seed_from_u64(0),
+            nodes: vec![],
+            parents: vec![],
+            ranks: vec![],
+            visited: HashSet::new(),
+            unvisited: vec![],
+            all: vec![],
+            num_sets: width * height,
+            trail: Trail::new(TRAIL_LEN),
+        }
+    }
+
+    fn handle_position(&mut self, pos: Pos, emit: &mut dyn FnMut(Event)) {
+        let neighbor = pos.get_random_neighbor(self.width, self.height, &mut self.rng);
         if let Some(neighbor) = neighbor {
             let neighbor_pos = neighbor.get_pos();
 
-            let this_set = self.nodes[pos.x][pos.y].set;
-            let neighbor_set = self.nodes[neighbor_pos.x][neighbor_pos.y].set;
+            let this_set = self.find(pos);
+            let neighbor_set = self.find(neighbor_pos);
 
             if this_set != neighbor_set {
                 self.num_sets -= 1;
 
-                for x in 0..GRID_WIDTH {
-                    for y in 0..GRID_HEIGHT {
-                        if self.nodes[x][y].set == neighbor_set {
-                            self.nodes[x][y].set = this_set;
-                        }
-                    }
+                // Hang the shorter tree under the taller one, so finding a root stays cheap.
+                let (from, into) = if self.ranks[this_set] < self.ranks[neighbor_set] {
+                    (this_set, neighbor_set)
+                } else {
+                    (neighbor_set, this_set)
+                };
+                self.parents[from] = into;
+                if self.ranks[from] == self.ranks[into] {
+                    self.ranks[into] += 1;
                 }
 
-                self.handle_neighbor(pos, neighbor);
+                pos.make_connection(&neighbor, &mut self.nodes);
+                emit(Event::Merged { from, into });
+                emit(Event::Carved {
+                    from: pos,
+                    to: neighbor_pos,
+                });
             }
 
-            self.visited.insert(pos);
-            self.visited.insert(neighbor_pos);
-
-            self.last_posses.insert(0, pos);
-            self.last_posses.insert(0, neighbor_pos);
-            self.last_posses.truncate(LAST_POSSES_LEN);
-        }
-    }
-
-    fn handle_neighbor(&mut self, pos: Pos, neighbor: Direction) {
-        match neighbor {
-            Direction::Left(_) => {
-                self.nodes[pos.x][pos.y].left = false;
+            if self.visited.insert(pos) {
+                emit(Event::Visited(pos));
             }
-            Direction::Right(next_pos) => {
-                self.nodes[next_pos.x][next_pos.y].left = false;
-            }
-            Direction::Up(_) => {
-                self.nodes[pos.x][pos.y].up = false;
-            }
-            Direction::Down(next_pos) => {
-                self.nodes[next_pos.x][next_pos.y].up = false;
+            if self.visited.insert(neighbor_pos) {
+                emit(Event::Visited(neighbor_pos));
             }
+
+            self.trail.push(pos, emit);
+            self.trail.push(neighbor_pos, emit);
         }
     }
-}
 
-impl Maze for Kruskal {
-    fn new() -> Self {
-        let mut all = vec![];
-        for x in 0..GRID_WIDTH {
-            for y in 0..GRID_HEIGHT {
-                all.push(Pos::new(x, y));
-            }
-        }
+    /// The set `pos` is in, pointing every cell on the way straight at it.
+    fn find(&mut self, pos: Pos) -> usize {
+        let cell = pos.x * self.height + pos.y;
 
-        all.shuffle(&mut thread_rng());
+        let mut root = cell;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
 
-        Self {
-            nodes: vec![],
-            visited: HashSet::new(),
-            unvisited: all.clone(),
-            all,
-            num_sets: GRID_WIDTH * GRID_HEIGHT,
-            last_posses: vec![],
+        let mut current = cell;
+        while self.parents[current] != root {
+            current = std::mem::replace(&mut self.parents[current], root);
         }
+
+        root
     }
+}
 
+impl Maze for Kruskal {
     fn complete(&self) -> bool {
         self.num_sets == 1
     }
 
-    fn reset(&mut self) {
-        let mut set = 0;
+    fn reset_with_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+
         self.nodes.clear();
-        for x in 0..GRID_WIDTH {
+        for x in 0..self.width {
             self.nodes.push(vec![]);
-            for _y in 0..GRID_HEIGHT {
-                self.nodes[x].push(KruskalNode::new(set));
-                set += 1;
+            for _y in 0..self.height {
+                self.nodes[x].push(Node::new());
             }
         }
+        self.parents = (0..self.width * self.height).collect();
+        self.ranks = vec![0; self.width * self.height];
 
         self.visited.clear();
 
         self.all.clear();
-        for x in 0..GRID_WIDTH {
-            for y in 0..GRID_HEIGHT {
+        for x in 0..self.width {
+            for y in 0..self.height {
                 self.all.push(Pos::new(x, y));
             }
         }
 
-        self.all.shuffle(&mut thread_rng());
+        self.all.shuffle(&mut self.rng);
         self.unvisited = self.all.clone();
-        self.num_sets = GRID_WIDTH * GRID_HEIGHT;
+        self.num_sets = self.width * self.height;
+        self.trail.clear();
     }
 
-    fn generate(&mut self) {
+    fn step(&mut self, emit: &mut dyn FnMut(Event)) {
         if let Some(pos) = self.unvisited.pop() {
-            self.handle_position(pos);
-        } else if let Some(pos) = self.all.clone().choose(&mut thread_rng()) {
-            self.handle_position(*pos);
+            self.handle_position(pos, emit);
+        } else if let Some(pos) = self.all.choose(&mut self.rng).copied() {
+            self.handle_position(pos, emit);
         }
 
         if self.complete() {
-            self.last_posses.clear();
+            self.trail.fade(emit);
         }
     }
 
-    fn draw(&self, d: &mut RaylibDrawHandle) {
-        d.clear_background(Color::BLACK);
-
-        for (x, row) in self.nodes.iter().enumerate() {
-            for y in 0..row.len() {
-                let node = &self.nodes[x][y];
-                let pos = Pos::new(x, y);
-
-                let screen_x = x as i32 * NODE_SIZE_I;
-                let screen_y = y as i32 * NODE_SIZE_I;
-
-                if self.visited.contains(&pos) {
-                    d.draw_rectangle(screen_x, screen_y, NODE_SIZE_I, NODE_SIZE_I, Color::WHITE);
-
-                    if node.up {
-                        d.draw_line(
-                            screen_x,
-                            screen_y,
-                            screen_x + NODE_SIZE_I,
-                            screen_y,
-                            Color::BLACK,
-                        );
-                    }
-                    if node.left {
-                        d.draw_line(
-                            screen_x,
-                            screen_y,
-                            screen_x,
-                            screen_y + NODE_SIZE_I,
-                            Color::BLACK,
-                        );
-                    }
-                }
-            }
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn nodes(&self) -> &[Vec<Node>] {
+        &self.nodes
+    }
+
+    fn is_visited(&self, pos: Pos) -> bool {
+        // A single cell is complete without ever being looked at.
+        self.complete() || self.visited.contains(&pos)
+    }
+
+    fn highlights(&self) -> Vec<(Pos, Highlight)> {
+        if self.complete() {
+            return vec![];
         }
 
-        if !self.complete() {
-            for pos in &self.last_posses {
-                d.draw_rectangle(
-                    pos.x as i32 * NODE_SIZE_I,
-                    pos.y as i32 * NODE_SIZE_I,
-                    NODE_SIZE_I,
-                    NODE_SIZE_I,
-                    Color::RED,
-                );
-            }
+        self.trail
+            .iter()
+            .map(|&pos| (pos, Highlight::Trail))
+            .collect()
+    }
+
+    fn highlight(&self, pos: Pos) -> Option<Highlight> {
+        (!self.complete() && self.trail.contains(pos)).then_some(Highlight::Trail)
+    }
+
+    fn visited_cells(&self) -> usize {
+        if self.complete() {
+            self.width * self.height
+        } else {
+            self.visited.len()
         }
     }
 }
@@ -1,124 +1,151 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use rand::{seq::IteratorRandom, thread_rng};
-use raylib::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::{helpers::*, maze::Maze, GRID_HEIGHT, GRID_WIDTH, NODE_SIZE_I};
+use crate::{events::Event, helpers::*, maze::Maze, theme::Highlight};
 
 #[derive(Clone)]
 pub struct Prim {
+    width: usize,
+    height: usize,
+    seed: u64,
+    rng: StdRng,
     nodes: Vec<Vec<Node>>,
     visited: HashSet<Pos>,
-    edges: HashSet<Pos>,
+    /// The frontier, in no particular order so a random cell of it can be picked in constant
+    /// time.
+    edges: Vec<Pos>,
+    /// Where each cell of the frontier is in `edges`.
+    edge_indices: HashMap<Pos, usize>,
 }
 
 impl Prim {
-    fn handle_neighbor(&mut self, pos: Pos, neighbor: Direction) {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            seed: 0,
+            rng: StdRng::seed_from_u64(0),
+            nodes: vec![],
+            visited: HashSet::new(),
+            edges: vec![],
+            edge_indices: HashMap::new(),
+        }
+    }
+
+    /// Adds `pos` to the frontier. Returns whether it wasn't on it already.
+    fn insert_edge(&mut self, pos: Pos) -> bool {
+        if self.edge_indices.contains_key(&pos) {
+            return false;
+        }
+        self.edge_indices.insert(pos, self.edges.len());
+        self.edges.push(pos);
+        true
+    }
+
+    /// Takes `pos` off the frontier. Returns whether it was on it.
+    fn remove_edge(&mut self, pos: Pos) -> bool {
+        let Some(index) = self.edge_indices.remove(&pos) else {
+            return false;
+        };
+        self.edges.swap_remove(index);
+        if let Some(&moved) = self.edges.get(index) {
+            self.edge_indices.insert(moved, index);
+        }
+        true
+    }
+
+    fn handle_neighbor(&mut self, pos: Pos, neighbor: Direction, emit: &mut dyn FnMut(Event)) {
         let next_pos = neighbor.get_pos();
 
         self.visited.insert(next_pos);
-        if next_pos.has_neighbors_in(&self.visited) {
-            self.edges.insert(next_pos);
+        emit(Event::Visited(next_pos));
+        if next_pos.has_neighbors_in(&self.visited, self.width, self.height)
+            && self.insert_edge(next_pos)
+        {
+            emit(Event::Pushed(next_pos));
         }
 
-        if !pos.has_neighbors_in(&self.visited) {
-            self.edges.remove(&pos);
+        if !pos.has_neighbors_in(&self.visited, self.width, self.height) && self.remove_edge(pos) {
+            emit(Event::Popped(pos));
         }
     }
 }
 
 impl Maze for Prim {
-    fn new() -> Self {
-        Self {
-            nodes: vec![],
-            visited: HashSet::new(),
-            edges: HashSet::new(),
-        }
-    }
-
     fn complete(&self) -> bool {
-        self.visited.len() == GRID_WIDTH * GRID_HEIGHT && self.edges.is_empty()
+        self.visited.len() == self.width * self.height && self.edges.is_empty()
     }
 
-    fn reset(&mut self) {
+    fn reset_with_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+
         self.nodes.clear();
-        for x in 0..GRID_WIDTH {
+        for x in 0..self.width {
             self.nodes.push(vec![]);
-            for _y in 0..GRID_HEIGHT {
+            for _y in 0..self.height {
                 self.nodes[x].push(Node::new());
             }
         }
 
         self.visited.clear();
         self.edges.clear();
+        self.edge_indices.clear();
 
-        let start_pos = rand::random();
+        let start_pos = Pos::random(self.width, self.height, &mut self.rng);
 
         self.visited.insert(start_pos);
-        self.edges.insert(start_pos);
+        self.insert_edge(start_pos);
     }
 
-    fn generate(&mut self) {
-        if let Some(pos) = self.edges.clone().iter().choose(&mut thread_rng()) {
-            let neighbor = pos.get_random_neighbor_not_in(&self.visited);
-            if let Some(neighbor) = neighbor {
-                pos.make_connection(&neighbor, &mut self.nodes);
-                self.handle_neighbor(*pos, neighbor);
-            } else {
-                self.edges.remove(pos);
-            }
+    fn step(&mut self, emit: &mut dyn FnMut(Event)) {
+        if self.edges.is_empty() {
+            return;
         }
-    }
 
-    fn draw(&self, d: &mut RaylibDrawHandle) {
-        d.clear_background(Color::BLACK);
-
-        for (x, row) in self.nodes.iter().enumerate() {
-            for y in 0..row.len() {
-                let node = &self.nodes[x][y];
-                let pos = Pos::new(x, y);
-
-                let screen_x = x as i32 * NODE_SIZE_I;
-                let screen_y = y as i32 * NODE_SIZE_I;
-
-                if self.visited.contains(&pos) {
-                    if self.edges.contains(&pos) {
-                        d.draw_rectangle(
-                            screen_x,
-                            screen_y,
-                            NODE_SIZE_I,
-                            NODE_SIZE_I,
-                            Color::PURPLE,
-                        );
-                    } else {
-                        d.draw_rectangle(
-                            screen_x,
-                            screen_y,
-                            NODE_SIZE_I,
-                            NODE_SIZE_I,
-                            Color::WHITE,
-                        );
-                    }
-                    if node.up {
-                        d.draw_line(
-                            screen_x,
-                            screen_y,
-                            screen_x + NODE_SIZE_I,
-                            screen_y,
-                            Color::BLACK,
-                        );
-                    }
-                    if node.left {
-                        d.draw_line(
-                            screen_x,
-                            screen_y,
-                            screen_x,
-                            screen_y + NODE_SIZE_I,
-                            Color::BLACK,
-                        );
-                    }
-                }
-            }
+        let pos = self.edges[self.rng.gen_range(0..self.edges.len())];
+        let neighbor =
+            pos.get_random_neighbor_not_in(&self.visited, self.width, self.height, &mut self.rng);
+        if let Some(neighbor) = neighbor {
+            pos.make_connection(&neighbor, &mut self.nodes);
+            emit(Event::Carved {
+                from: pos,
+                to: neighbor.get_pos(),
+            });
+            self.handle_neighbor(pos, neighbor, emit);
+        } else {
+            self.remove_edge(pos);
+            emit(Event::Popped(pos));
         }
     }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn nodes(&self) -> &[Vec<Node>] {
+        &self.nodes
+    }
+
+    fn is_visited(&self, pos: Pos) -> bool {
+        self.visited.contains(&pos)
+    }
+
+    fn highlights(&self) -> Vec<(Pos, Highlight)> {
+        self.edges
+            .iter()
+            .map(|&pos| (pos, Highlight::Frontier))
+            .collect()
+    }
+
+    fn highlight(&self, pos: Pos) -> Option<Highlight> {
+        self.edge_indices
+            .contains_key(&pos)
+            .then_some(Highlight::Frontier)
+    }
+
+    fn visited_cells(&self) -> usize {
+        self.visited.len()
+    }
 }
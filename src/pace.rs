@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+/// The longest frame `Pacer` makes up for, so a stalled frame, such as while the window is
+/// being dragged, doesn't run a burst of steps afterwards.
+pub const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+
+/// Turns the time frames take into a number of steps to run, so a generator goes at the same
+/// speed whatever the frame rate.
+///
+/// Time is accumulated and spent in fixed steps of `1 / steps_per_second`, carrying whatever is
+/// left over to the next frame.
+#[derive(Clone, Default, Debug)]
+pub struct Pacer {
+    /// Time not spent on steps yet, in seconds.
+    accumulator: f64,
+}
+
+impl Pacer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets the time left over from earlier frames.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+
+    /// Adds a frame that took `elapsed` and returns how many steps are due.
+    pub fn advance(&mut self, elapsed: Duration, steps_per_second: f64) -> usize {
+        if steps_per_second <= 0.0 {
+            self.accumulator = 0.0;
+            return 0;
+        }
+
+        self.accumulator += elapsed.min(MAX_FRAME_TIME).as_secs_f64();
+
+        let steps = (self.accumulator * steps_per_second).floor();
+        self.accumulator -= steps / steps_per_second;
+        steps as usize
+    }
+}
@@ -0,0 +1,233 @@
+use std::time::Duration;
+
+#[cfg(feature = "viewer")]
+use raylib::prelude::*;
+
+#[cfg(feature = "viewer")]
+use crate::{color::Color, render::RenderOptions};
+use crate::{
+    helpers::{Direction, Node, Pos},
+    solve::solve,
+};
+
+/// How many cells around the player fog of war reveals, in every direction.
+pub const SIGHT: usize = 2;
+
+/// How many cells of each path the replay draws per second.
+const REPLAY_SPEED: f64 = 30.0;
+
+/// A way the player can try to move.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Move {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A user finding their way through a finished maze from the entrance in the top left corner
+/// to the exit in the bottom right one.
+///
+/// The timer starts with the first move and stops at the exit, after which the path taken can
+/// be replayed next to the shortest one.
+#[derive(Clone, Debug)]
+pub struct Game {
+    nodes: Vec<Vec<Node>>,
+    pub start: Pos,
+    pub goal: Pos,
+    /// Every cell the player has stood on in order, starting with `start` and ending where
+    /// they are now.
+    path: Vec<Pos>,
+    /// The cells fog of war has revealed, indexed as `seen[x][y]`.
+    seen: Vec<Vec<bool>>,
+    /// The shortest path from `start` to `goal`.
+    solution: Vec<Pos>,
+    pub fog: bool,
+    elapsed: Duration,
+    /// How many cells of each path the replay shows, once it has started.
+    replay: Option<f64>,
+}
+
+impl Game {
+    /// Starts at the entrance of the maze `nodes` describe, which must be perfect, or at least
+    /// have a way from the entrance to the exit.
+    pub fn new(nodes: &[Vec<Node>]) -> Self {
+        let width = nodes.len();
+        let height = nodes.first().map_or(0, Vec::len);
+        let start = Pos::new(0, 0);
+        let goal = Pos::new(width.saturating_sub(1), height.saturating_sub(1));
+
+        let mut game = Self {
+            nodes: nodes.to_vec(),
+            start,
+            goal,
+            path: vec![start],
+            seen: vec![vec![false; height]; width],
+            solution: solve(nodes, start, goal).unwrap_or_default(),
+            fog: false,
+            elapsed: Duration::ZERO,
+            replay: None,
+        };
+        game.reveal();
+        game
+    }
+
+    pub fn position(&self) -> Pos {
+        *self.path.last().expect("the path starts with the entrance")
+    }
+
+    /// The cells the player has stood on so far, in order.
+    pub fn path(&self) -> &[Pos] {
+        &self.path
+    }
+
+    /// The shortest path from the entrance to the exit, including both.
+    pub fn solution(&self) -> &[Pos] {
+        &self.solution
+    }
+
+    /// How many times the player has moved.
+    pub fn moves(&self) -> usize {
+        self.path.len() - 1
+    }
+
+    /// How many moves the shortest path takes.
+    pub fn optimal_moves(&self) -> usize {
+        self.solution.len().saturating_sub(1)
+    }
+
+    /// How long the player has been going, or took to reach the exit.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn finished(&self) -> bool {
+        self.position() == self.goal
+    }
+
+    /// Whether fog of war lets the player see `pos`.
+    pub fn is_revealed(&self, pos: Pos) -> bool {
+        !self.fog || self.seen[pos.x][pos.y]
+    }
+
+    /// Moves the player a cell in `direction`, unless a wall is in the way or they have
+    /// already reached the exit. Returns whether they moved.
+    pub fn step(&mut self, direction: Move) -> bool {
+        if self.finished() {
+            return false;
+        }
+
+        let next = self
+            .position()
+            .open_neighbors(&self.nodes)
+            .into_iter()
+            .find(|neighbor| {
+                matches!(
+                    (neighbor, direction),
+                    (Direction::Up(_), Move::Up)
+                        | (Direction::Down(_), Move::Down)
+                        | (Direction::Left(_), Move::Left)
+                        | (Direction::Right(_), Move::Right)
+                )
+            });
+        let Some(next) = next else {
+            return false;
+        };
+
+        self.path.push(next.get_pos());
+        self.reveal();
+        if self.finished() {
+            self.replay = Some(0.0);
+        }
+        true
+    }
+
+    /// Runs the timer, once the player has started and until they finish, and the replay.
+    pub fn tick(&mut self, elapsed: Duration) {
+        if self.moves() > 0 && !self.finished() {
+            self.elapsed += elapsed;
+        }
+        if let Some(shown) = &mut self.replay {
+            *shown += elapsed.as_secs_f64() * REPLAY_SPEED;
+        }
+    }
+
+    /// Plays the replay from the start again, once the player has reached the exit.
+    pub fn replay(&mut self) {
+        if self.finished() {
+            self.replay = Some(0.0);
+        }
+    }
+
+    /// How many cells of the player's path and of the shortest path the replay shows, if it
+    /// has started.
+    pub fn replay_shown(&self) -> Option<(usize, usize)> {
+        let shown = self.replay?.floor() as usize + 1;
+        Some((shown.min(self.path.len()), shown.min(self.solution.len())))
+    }
+
+    /// Marks the cells around the player as seen.
+    fn reveal(&mut self) {
+        let pos = self.position();
+        let width = self.seen.len();
+        let height = self.seen.first().map_or(0, Vec::len);
+        for x in pos.x.saturating_sub(SIGHT)..(pos.x + SIGHT + 1).min(width) {
+            for y in pos.y.saturating_sub(SIGHT)..(pos.y + SIGHT + 1).min(height) {
+                self.seen[x][y] = true;
+            }
+        }
+    }
+}
+
+/// Draws `game` over its maze, which is drawn with `options` at the origin: the fog, the
+/// entrance and exit, the path the player took and the player, or the replay once they have
+/// reached the exit.
+#[cfg(feature = "viewer")]
+pub fn draw_game(d: &mut RaylibDrawHandle, game: &Game, options: &RenderOptions) {
+    let theme = &options.theme;
+    let cell = options.cell_size;
+    let center = |pos: Pos| Vector2::new((pos.x as f32 + 0.5) * cell, (pos.y as f32 + 0.5) * cell);
+
+    if game.fog {
+        for (x, column) in game.seen.iter().enumerate() {
+            for (y, &seen) in column.iter().enumerate() {
+                if !seen {
+                    // Reach over the walls at the edge so nothing shows through the seams.
+                    let fog = Rectangle::new(
+                        x as f32 * cell - 1.0,
+                        y as f32 * cell - 1.0,
+                        cell + 2.0,
+                        cell + 2.0,
+                    );
+                    d.draw_rectangle_rec(fog, theme.background);
+                }
+            }
+        }
+    }
+
+    let marker = cell / 3.0;
+    for (pos, color) in [(game.start, theme.frontier), (game.goal, theme.trail)] {
+        if game.is_revealed(pos) {
+            d.draw_circle_v(center(pos), marker, color);
+        }
+    }
+
+    let thickness = (cell / 4.0).max(1.0);
+    let draw_path = |d: &mut RaylibDrawHandle, path: &[Pos], color: Color| {
+        for pair in path.windows(2) {
+            d.draw_line_ex(center(pair[0]), center(pair[1]), thickness, color);
+        }
+    };
+
+    match game.replay_shown() {
+        Some((player, optimal)) => {
+            draw_path(d, &game.solution[..optimal], theme.frontier.fade(0.8));
+            draw_path(d, &game.path[..player], theme.active.fade(0.8));
+            d.draw_circle_v(center(game.path[player - 1]), marker, theme.active);
+        }
+        None => {
+            draw_path(d, &game.path, theme.trail.fade(0.6));
+            d.draw_circle_v(center(game.position()), marker, theme.active);
+        }
+    }
+}
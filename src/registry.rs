@@ -0,0 +1,187 @@
+use std::fmt;
+
+use clap::{builder::PossibleValue, ValueEnum};
+use rand::{distributions::Standard, prelude::Distribution, seq::SliceRandom};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{formats::Topology, maze::Maze, mazes::*};
+
+/// Whether a generator picks every possible maze of a grid with the same probability.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bias {
+    Uniform,
+    /// Some mazes come up more often than others, giving the generator a recognizable texture.
+    Biased,
+}
+
+/// Everything the visualizer, the command line and the config need to know about a generator.
+#[derive(Debug)]
+pub struct Generator {
+    /// How the generator is named on the command line and in config files.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Steps per second: the steps per frame the generator used to run at, times 60 frames
+    /// per second.
+    pub default_speed: usize,
+    pub topologies: &'static [Topology],
+    pub bias: Bias,
+    /// Makes a generator for a grid of this width and height, which needs a reset before it
+    /// can step.
+    pub new: fn(usize, usize) -> Box<dyn Maze>,
+}
+
+/// Every generator, in the order the default playlist plays them. Adding a generator only
+/// takes an entry here.
+pub const GENERATORS: &[Generator] = &[
+    Generator {
+        id: "depth-first-search",
+        name: "Depth First Search",
+        description: "A random walk that backtracks at dead ends, leaving long winding corridors",
+        default_speed: 6_600,
+        topologies: &[Topology::Rectangular],
+        bias: Bias::Biased,
+        new: |width, height| Box::new(DepthFirstSearch::new(width, height)),
+    },
+    Generator {
+        id: "binary-tree",
+        name: "Binary Tree",
+        description: "Opens every cell up or left, leaving two open sides along the top and left",
+        default_speed: 1_500,
+        topologies: &[Topology::Rectangular],
+        bias: Bias::Biased,
+        new: |width, height| Box::new(BinaryTree::new(width, height)),
+    },
+    Generator {
+        id: "hunt-and-kill",
+        name: "Hunt and Kill",
+        description: "A random walk that scans for a new start next to the maze at dead ends",
+        default_speed: 3_000,
+        topologies: &[Topology::Rectangular],
+        bias: Bias::Biased,
+        new: |width, height| Box::new(HuntAndKill::new(width, height)),
+    },
+    Generator {
+        id: "prim",
+        name: "Prim's",
+        description:
+            "Grows the maze from a random cell on its frontier, leaving many short dead ends",
+        default_speed: 1_800,
+        topologies: &[Topology::Rectangular],
+        bias: Bias::Biased,
+        new: |width, height| Box::new(Prim::new(width, height)),
+    },
+    Generator {
+        id: "kruskal",
+        name: "Kruskal's",
+        description: "Joins random neighboring cells that aren't connected yet, all over the grid",
+        default_speed: 7_200,
+        topologies: &[Topology::Rectangular],
+        bias: Bias::Biased,
+        new: |width, height| Box::new(Kruskal::new(width, height)),
+    },
+    Generator {
+        id: "aldous-broder",
+        name: "Aldous Broder",
+        description: "A random walk that carves into every cell it reaches first, slow but uniform",
+        default_speed: 120_000,
+        topologies: &[Topology::Rectangular],
+        bias: Bias::Uniform,
+        new: |width, height| Box::new(AldousBroder::new(width, height)),
+    },
+];
+
+/// A generator in `GENERATORS`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Algorithm(usize);
+
+static ALL: [Algorithm; GENERATORS.len()] = all();
+
+const fn all<const N: usize>() -> [Algorithm; N] {
+    let mut all = [Algorithm(0); N];
+    let mut i = 0;
+    while i < N {
+        all[i] = Algorithm(i);
+        i += 1;
+    }
+    all
+}
+
+impl Algorithm {
+    /// Every algorithm, in the order of `GENERATORS`.
+    pub fn all() -> &'static [Algorithm] {
+        &ALL
+    }
+
+    /// Finds an algorithm by its `id`.
+    pub fn named(id: &str) -> Option<Self> {
+        GENERATORS
+            .iter()
+            .position(|generator| generator.id == id)
+            .map(Algorithm)
+    }
+
+    pub fn generator(&self) -> &'static Generator {
+        &GENERATORS[self.0]
+    }
+
+    pub fn id(&self) -> &'static str {
+        self.generator().id
+    }
+
+    pub fn default_speed(&self) -> usize {
+        self.generator().default_speed
+    }
+
+    /// A new, not yet reset maze of this algorithm.
+    pub fn new_maze(&self, width: usize, height: usize) -> Box<dyn Maze> {
+        (self.generator().new)(width, height)
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.generator().name)
+    }
+}
+
+impl fmt::Debug for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl ValueEnum for Algorithm {
+    fn value_variants<'a>() -> &'a [Self] {
+        &ALL
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(self.id()).help(self.generator().description))
+    }
+}
+
+impl Serialize for Algorithm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for Algorithm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Algorithm::named(&id).ok_or_else(|| {
+            let ids: Vec<&str> = GENERATORS.iter().map(|generator| generator.id).collect();
+            de::Error::custom(format!(
+                "unknown algorithm {id:?}, expected one of {}",
+                ids.join(", ")
+            ))
+        })
+    }
+}
+
+impl Distribution<Algorithm> for Standard {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Algorithm {
+        *ALL.choose(rng).expect("there is at least one generator")
+    }
+}
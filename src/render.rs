@@ -0,0 +1,425 @@
+use std::collections::BTreeSet;
+#[cfg(feature = "viewer")]
+use std::collections::HashMap;
+
+#[cfg(feature = "viewer")]
+use raylib::prelude::*;
+
+#[cfg(feature = "viewer")]
+use crate::{color::Color, maze::Maze};
+use crate::{
+    formats::Segment,
+    helpers::{Node, Pos},
+    theme::Theme,
+    NODE_SIZE,
+};
+
+/// How `draw_maze` lays out a maze on screen, all sizes in pixels.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RenderOptions {
+    pub theme: Theme,
+    /// The distance between the walls on either side of a cell.
+    pub cell_size: f32,
+    pub wall_thickness: f32,
+    /// Shrinks every passage by this much on each side, leaving the background between
+    /// cells that are not connected.
+    pub inset: f32,
+    /// How round the corners of passages are, from 0 for square to 1 for fully round. Walls
+    /// get round ends when it is above 0.
+    pub roundness: f32,
+    /// Asks for a multisampled window so edges are smoothed. Only takes effect when the
+    /// window is created.
+    pub anti_alias: bool,
+    /// Whether cells the generator is working on are drawn in the theme's highlight colors.
+    pub highlights: bool,
+}
+
+impl RenderOptions {
+    /// How many pixels a maze of `width` by `height` cells takes up.
+    pub fn size(&self, width: usize, height: usize) -> (i32, i32) {
+        (
+            (width as f32 * self.cell_size).ceil() as i32,
+            (height as f32 * self.cell_size).ceil() as i32,
+        )
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            cell_size: NODE_SIZE as f32,
+            wall_thickness: 1.0,
+            inset: 0.0,
+            roundness: 0.0,
+            anti_alias: false,
+            highlights: true,
+        }
+    }
+}
+
+/// The cells `Renderer` repaints after some cells changed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Repaint {
+    /// The changed cells and every cell around them, which are filled again after the
+    /// changed ones are cleared.
+    pub fill: BTreeSet<Pos>,
+    /// The cells whose walls are drawn again over the fills. Walls reach half their thickness
+    /// past the ends of their grid lines, so a wall can cover a corner of a filled cell without
+    /// running along its edge. These are every cell with a wall on the edge or a corner of a
+    /// filled one: the filled cells and one more cell around them.
+    pub walls: BTreeSet<Pos>,
+}
+
+impl Repaint {
+    /// What repainting the `dirty` cells of a `width` by `height` maze takes.
+    pub fn new(dirty: &[Pos], width: usize, height: usize) -> Self {
+        Self {
+            fill: around(dirty, 1, width, height),
+            walls: around(dirty, 2, width, height),
+        }
+    }
+}
+
+/// Every cell at most `reach` cells away from one of `cells` in both directions.
+fn around(cells: &[Pos], reach: usize, width: usize, height: usize) -> BTreeSet<Pos> {
+    let mut around = BTreeSet::new();
+    for pos in cells {
+        for x in pos.x.saturating_sub(reach)..(pos.x + reach + 1).min(width) {
+            for y in pos.y.saturating_sub(reach)..(pos.y + reach + 1).min(height) {
+                around.insert(Pos::new(x, y));
+            }
+        }
+    }
+    around
+}
+
+/// The walls drawn for the cell at `pos`: its top and left ones, and its right and bottom ones
+/// on the edge of the grid.
+pub fn cell_walls(nodes: &[Vec<Node>], pos: Pos) -> Vec<Segment> {
+    let node = nodes[pos.x][pos.y];
+    let (left, top) = (pos.x, pos.y);
+    let (right, bottom) = (left + 1, top + 1);
+    let segment = |from: (usize, usize), to: (usize, usize)| Segment {
+        from: Pos::new(from.0, from.1),
+        to: Pos::new(to.0, to.1),
+    };
+
+    let mut walls = vec![];
+    if node.up {
+        walls.push(segment((left, top), (right, top)));
+    }
+    if node.left {
+        walls.push(segment((left, top), (left, bottom)));
+    }
+    if right == nodes.len() {
+        walls.push(segment((right, top), (right, bottom)));
+    }
+    if bottom == nodes[pos.x].len() {
+        walls.push(segment((left, bottom), (right, bottom)));
+    }
+    walls
+}
+
+/// Draws every cell `maze` has reached as a passage with its walls, and its highlights on top.
+///
+/// Only the area the maze covers is painted over, so other things drawn next to it are kept.
+#[cfg(feature = "viewer")]
+pub fn draw_maze<D, M>(d: &mut D, maze: &M, options: &RenderOptions)
+where
+    D: RaylibDraw,
+    M: Maze + ?Sized,
+{
+    let theme = &options.theme;
+
+    let nodes = maze.nodes();
+    let width = nodes.len();
+    let height = nodes.first().map_or(0, Vec::len);
+
+    let (pixel_width, pixel_height) = options.size(width, height);
+    d.draw_rectangle(0, 0, pixel_width, pixel_height, theme.background);
+
+    let highlights = highlight_colors(maze, options);
+
+    for x in 0..width {
+        for y in 0..height {
+            let pos = Pos::new(x, y);
+            if let Some(color) = fill(maze, highlights.get(&pos).copied(), pos, theme) {
+                draw_cell(d, maze, pos, color, options);
+            }
+        }
+    }
+
+    for x in 0..width {
+        for y in 0..height {
+            let pos = Pos::new(x, y);
+            if maze.is_visited(pos) {
+                draw_walls(d, maze, pos, theme.wall, options);
+            }
+        }
+    }
+}
+
+/// Keeps a maze drawn in a texture and only repaints the cells that changed since the last
+/// frame, so drawing a frame costs as much as the generator changed rather than the size of the
+/// grid. Which cells changed comes from the steps' events, as `Timeline::take_changes` collects
+/// them.
+#[cfg(feature = "viewer")]
+pub struct Renderer {
+    pub options: RenderOptions,
+    texture: Option<RenderTexture2D>,
+    drawn: Option<Drawn>,
+    /// Set once creating the texture failed, after which every frame is drawn from scratch.
+    unsupported: bool,
+}
+
+/// What the texture currently shows.
+#[cfg(feature = "viewer")]
+struct Drawn {
+    options: RenderOptions,
+    seed: u64,
+    size: (usize, usize),
+}
+
+#[cfg(feature = "viewer")]
+impl Renderer {
+    pub fn new(options: RenderOptions) -> Self {
+        Self {
+            options,
+            texture: None,
+            drawn: None,
+            unsupported: false,
+        }
+    }
+
+    /// Makes the next call to `render` repaint the whole maze.
+    pub fn invalidate(&mut self) {
+        self.drawn = None;
+    }
+
+    /// Brings the texture up to date with `maze`. Call it outside of `begin_drawing`.
+    ///
+    /// Fails if the texture can't be created, in which case `draw` draws every frame from
+    /// scratch from then on.
+    ///
+    /// Only the `changed` cells are repainted, along with the cells around them, or everything
+    /// if `changed` is `None` or the options, the size or the seed of the maze changed since
+    /// the last call.
+    pub fn render<M: Maze + ?Sized>(
+        &mut self,
+        mut rl: &mut RaylibHandle,
+        thread: &RaylibThread,
+        maze: &M,
+        changed: Option<&[Pos]>,
+    ) -> Result<(), String> {
+        if self.unsupported {
+            return Ok(());
+        }
+
+        let nodes = maze.nodes();
+        let width = nodes.len();
+        let height = nodes.first().map_or(0, Vec::len);
+
+        let size = self.options.size(width, height);
+        if self
+            .texture
+            .as_ref()
+            .is_none_or(|texture| (texture.width(), texture.height()) != size)
+        {
+            self.drawn = None;
+            self.texture = None;
+            match rl.load_render_texture(thread, size.0 as u32, size.1 as u32) {
+                Ok(texture) => self.texture = Some(texture),
+                Err(err) => {
+                    self.unsupported = true;
+                    return Err(err);
+                }
+            }
+        }
+
+        let Some(texture) = self.texture.as_mut() else {
+            return Ok(());
+        };
+        let options = &self.options;
+        let theme = &options.theme;
+
+        let up_to_date = self.drawn.as_ref().is_some_and(|drawn| {
+            drawn.options == *options && drawn.seed == maze.seed() && drawn.size == (width, height)
+        });
+        let dirty = match changed {
+            Some(changed) if up_to_date => changed,
+            _ => {
+                let mut d = rl.begin_texture_mode(thread, texture);
+                draw_maze(&mut d, maze, options);
+
+                self.drawn = Some(Drawn {
+                    options: options.clone(),
+                    seed: maze.seed(),
+                    size: (width, height),
+                });
+                return Ok(());
+            }
+        };
+
+        if dirty.is_empty() {
+            return Ok(());
+        }
+
+        // Walls and rounded ends reach half their thickness into the cells around them, so
+        // those are repainted too, over the cleared area.
+        let repaint = Repaint::new(dirty, width, height);
+
+        let mut d = rl.begin_texture_mode(thread, texture);
+
+        let cell = options.cell_size;
+        let half = options.wall_thickness / 2.0;
+        for pos in dirty {
+            let area = Rectangle::new(
+                pos.x as f32 * cell - half,
+                pos.y as f32 * cell - half,
+                cell + 2.0 * half,
+                cell + 2.0 * half,
+            );
+            d.draw_rectangle_rec(area, theme.background);
+        }
+
+        for &pos in &repaint.fill {
+            let highlight = maze
+                .highlight(pos)
+                .filter(|_| options.highlights)
+                .map(|highlight| theme.highlight(highlight));
+            if let Some(color) = fill(maze, highlight, pos, theme) {
+                draw_cell(&mut d, maze, pos, color, options);
+            }
+        }
+        for &pos in &repaint.walls {
+            if maze.is_visited(pos) {
+                draw_walls(&mut d, maze, pos, theme.wall, options);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws the texture `render` painted, or `maze` from scratch if there is none, with its
+    /// top left corner at `origin`.
+    pub fn draw<M: Maze + ?Sized>(&self, d: &mut RaylibDrawHandle, maze: &M, origin: Vector2) {
+        let Some(texture) = &self.texture else {
+            let camera = Camera2D {
+                offset: origin,
+                target: Vector2::zero(),
+                rotation: 0.0,
+                zoom: 1.0,
+            };
+            draw_maze(&mut d.begin_mode2D(camera), maze, &self.options);
+            return;
+        };
+
+        // Render textures are stored upside down.
+        let source = Rectangle::new(0.0, 0.0, texture.width() as f32, -texture.height() as f32);
+        d.draw_texture_rec(texture, source, origin, Color::WHITE);
+    }
+}
+
+#[cfg(feature = "viewer")]
+fn highlight_colors<M: Maze + ?Sized>(maze: &M, options: &RenderOptions) -> HashMap<Pos, Color> {
+    if !options.highlights {
+        return HashMap::new();
+    }
+
+    let theme = &options.theme;
+    maze.highlights()
+        .into_iter()
+        .map(|(pos, highlight)| (pos, theme.highlight(highlight)))
+        .collect()
+}
+
+/// The color a cell is filled with, if it is drawn at all.
+#[cfg(feature = "viewer")]
+fn fill<M: Maze + ?Sized>(
+    maze: &M,
+    highlight: Option<Color>,
+    pos: Pos,
+    theme: &Theme,
+) -> Option<Color> {
+    match highlight {
+        Some(color) => Some(color),
+        None if maze.is_visited(pos) => Some(theme.passage),
+        None => None,
+    }
+}
+
+#[cfg(feature = "viewer")]
+fn draw_cell<D, M>(d: &mut D, maze: &M, pos: Pos, color: Color, options: &RenderOptions)
+where
+    D: RaylibDraw,
+    M: Maze + ?Sized,
+{
+    let cell = options.cell_size;
+    let inset = options.inset.clamp(0.0, cell / 2.0);
+    let (x, y) = (pos.x as f32 * cell, pos.y as f32 * cell);
+
+    let passage = Rectangle::new(x + inset, y + inset, cell - 2.0 * inset, cell - 2.0 * inset);
+    if options.roundness > 0.0 {
+        d.draw_rectangle_rounded(passage, options.roundness, 8, color);
+    } else {
+        d.draw_rectangle_rec(passage, color);
+    }
+
+    if inset == 0.0 {
+        return;
+    }
+
+    // Bridge the gap the inset leaves towards connected cells that are drawn too.
+    let node = maze.nodes()[pos.x][pos.y];
+    if !node.up && pos.y > 0 && maze.is_visited(Pos::new(pos.x, pos.y - 1)) {
+        let bridge = Rectangle::new(x + inset, y - inset, cell - 2.0 * inset, 2.0 * inset);
+        d.draw_rectangle_rec(bridge, color);
+    }
+    if !node.left && pos.x > 0 && maze.is_visited(Pos::new(pos.x - 1, pos.y)) {
+        let bridge = Rectangle::new(x - inset, y + inset, 2.0 * inset, cell - 2.0 * inset);
+        d.draw_rectangle_rec(bridge, color);
+    }
+}
+
+/// Draws the walls `cell_walls` gives for a cell, centered on the grid lines.
+#[cfg(feature = "viewer")]
+fn draw_walls<D, M>(d: &mut D, maze: &M, pos: Pos, color: Color, options: &RenderOptions)
+where
+    D: RaylibDraw,
+    M: Maze + ?Sized,
+{
+    let cell = options.cell_size;
+    let corner = |pos: Pos| Vector2::new(pos.x as f32 * cell, pos.y as f32 * cell);
+
+    for segment in cell_walls(maze.nodes(), pos) {
+        draw_wall(d, corner(segment.from), corner(segment.to), color, options);
+    }
+}
+
+#[cfg(feature = "viewer")]
+fn draw_wall<D: RaylibDraw>(
+    d: &mut D,
+    from: Vector2,
+    to: Vector2,
+    color: Color,
+    options: &RenderOptions,
+) {
+    let thickness = options.wall_thickness;
+    let half = thickness / 2.0;
+
+    if options.roundness > 0.0 {
+        d.draw_line_ex(from, to, thickness, color);
+        d.draw_circle_v(from, half, color);
+        d.draw_circle_v(to, half, color);
+    } else {
+        // Extending both ends by half the thickness makes walls meet in square corners.
+        let direction = (to - from).normalized();
+        d.draw_line_ex(
+            from - direction * half,
+            to + direction * half,
+            thickness,
+            color,
+        );
+    }
+}
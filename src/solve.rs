@@ -0,0 +1,40 @@
+use std::collections::VecDeque;
+
+use crate::helpers::{Node, Pos};
+
+/// Finds the shortest path from `start` to `goal` through the passages of `nodes`, including
+/// both ends. In a perfect maze this is the only path between them.
+pub fn solve(nodes: &[Vec<Node>], start: Pos, goal: Pos) -> Option<Vec<Pos>> {
+    let width = nodes.len();
+    let height = nodes.first().map_or(0, Vec::len);
+    if start.x >= width || start.y >= height || goal.x >= width || goal.y >= height {
+        return None;
+    }
+
+    let mut previous: Vec<Vec<Option<Pos>>> = vec![vec![None; height]; width];
+    let mut queue = VecDeque::from([start]);
+    previous[start.x][start.y] = Some(start);
+
+    while let Some(pos) = queue.pop_front() {
+        if pos == goal {
+            let mut path = vec![goal];
+            let mut current = goal;
+            while current != start {
+                current = previous[current.x][current.y]?;
+                path.push(current);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for neighbor in pos.open_neighbors(nodes) {
+            let next_pos = neighbor.get_pos();
+            if previous[next_pos.x][next_pos.y].is_none() {
+                previous[next_pos.x][next_pos.y] = Some(pos);
+                queue.push_back(next_pos);
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,61 @@
+use crate::{
+    helpers::{Direction, Node, Pos},
+    solve::solve,
+};
+
+/// How the cells of a maze are shaped and how long it takes to cross, which tells apart the
+/// textures different algorithms leave.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MazeStats {
+    pub cells: usize,
+    /// Cells with a single opening.
+    pub dead_ends: usize,
+    /// Cells with two openings across from each other.
+    pub straights: usize,
+    /// Cells with two openings at a right angle.
+    pub turns: usize,
+    /// Cells with three or four openings.
+    pub junctions: usize,
+    /// The number of cells on the path from the top left cell to the bottom right one, if
+    /// they are connected.
+    pub solution_length: Option<usize>,
+}
+
+impl MazeStats {
+    pub fn of(nodes: &[Vec<Node>]) -> Self {
+        let width = nodes.len();
+        let height = nodes.first().map_or(0, Vec::len);
+
+        let mut stats = Self {
+            cells: width * height,
+            ..Self::default()
+        };
+        if stats.cells == 0 {
+            return stats;
+        }
+
+        for x in 0..width {
+            for y in 0..height {
+                let openings = Pos::new(x, y).open_neighbors(nodes);
+                match openings.as_slice() {
+                    [] => {}
+                    [_] => stats.dead_ends += 1,
+                    [first, second] if is_horizontal(first) == is_horizontal(second) => {
+                        stats.straights += 1
+                    }
+                    [_, _] => stats.turns += 1,
+                    _ => stats.junctions += 1,
+                }
+            }
+        }
+
+        stats.solution_length =
+            solve(nodes, Pos::new(0, 0), Pos::new(width - 1, height - 1)).map(|path| path.len());
+
+        stats
+    }
+}
+
+fn is_horizontal(direction: &Direction) -> bool {
+    matches!(direction, Direction::Left(_) | Direction::Right(_))
+}
@@ -0,0 +1,195 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::color::Color;
+
+/// What a highlighted cell shows about the algorithm's progress, which the theme picks a
+/// color for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Highlight {
+    /// The cells the algorithm is carving from right now.
+    Active,
+    /// The cells it may carve into next.
+    Frontier,
+    /// The cells it passed through recently.
+    Trail,
+}
+
+/// The colors a maze is drawn with.
+///
+/// Themes are loaded from TOML files with a `"#rrggbb"` or `"#rrggbbaa"` string for every
+/// color. Colors a file leaves out are taken from the classic theme.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    #[serde(default)]
+    pub name: String,
+    /// Fills the cells that have not been reached yet.
+    #[serde(with = "hex")]
+    pub background: Color,
+    #[serde(with = "hex")]
+    pub wall: Color,
+    #[serde(with = "hex")]
+    pub passage: Color,
+    #[serde(with = "hex")]
+    pub active: Color,
+    #[serde(with = "hex")]
+    pub frontier: Color,
+    #[serde(with = "hex")]
+    pub trail: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Theme {
+    /// Black walls on white passages, with raylib's primary colors for highlights.
+    ///
+    /// It approximates the look from before themes, when every generator picked its own
+    /// highlight color. It keeps Depth First Search's green stack, Prim's purple frontier and
+    /// Aldous-Broder's sky blue trail. The other generators now use the color of what their
+    /// highlight shows, instead of Kruskal's red, Hunt and Kill's gold and Binary Tree's blue.
+    pub fn classic() -> Self {
+        Self {
+            name: "classic".to_string(),
+            background: Color::BLACK,
+            wall: Color::BLACK,
+            passage: Color::WHITE,
+            active: Color::GREEN,
+            frontier: Color::PURPLE,
+            trail: Color::SKYBLUE,
+        }
+    }
+
+    /// Light walls on dark passages, easier on the eyes in a dark room.
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".to_string(),
+            background: Color::new(0x11, 0x11, 0x1b, 0xff),
+            wall: Color::new(0x9a, 0x9e, 0xb5, 0xff),
+            passage: Color::new(0x1e, 0x1e, 0x2e, 0xff),
+            active: Color::new(0xa6, 0xe3, 0xa1, 0xff),
+            frontier: Color::new(0xcb, 0xa6, 0xf7, 0xff),
+            trail: Color::new(0x89, 0xb4, 0xfa, 0xff),
+        }
+    }
+
+    /// Highlights from the Okabe-Ito palette, which stay distinct under every common form of
+    /// color blindness.
+    pub fn colorblind() -> Self {
+        Self {
+            name: "colorblind".to_string(),
+            background: Color::BLACK,
+            wall: Color::BLACK,
+            passage: Color::WHITE,
+            active: Color::new(0x00, 0x72, 0xb2, 0xff),
+            frontier: Color::new(0xe6, 0x9f, 0x00, 0xff),
+            trail: Color::new(0xcc, 0x79, 0xa7, 0xff),
+        }
+    }
+
+    /// Highlights that differ in brightness as well as hue, so they can be told apart without
+    /// seeing color at all.
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".to_string(),
+            background: Color::BLACK,
+            wall: Color::BLACK,
+            passage: Color::WHITE,
+            active: Color::new(0x00, 0x2b, 0x7f, 0xff),
+            frontier: Color::new(0xff, 0xd7, 0x00, 0xff),
+            trail: Color::new(0x80, 0x80, 0x80, 0xff),
+        }
+    }
+
+    /// Every built-in theme, starting with the classic one.
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self::classic(),
+            Self::dark(),
+            Self::colorblind(),
+            Self::high_contrast(),
+        ]
+    }
+
+    /// The built-in theme called `name`.
+    pub fn named(name: &str) -> Option<Self> {
+        Self::builtin().into_iter().find(|theme| theme.name == name)
+    }
+
+    /// Finds a built-in theme by name, or loads `name` as a theme file.
+    pub fn find(name: &str) -> io::Result<Self> {
+        match Self::named(name) {
+            Some(theme) => Ok(theme),
+            None => Self::load(name),
+        }
+    }
+
+    pub fn highlight(&self, highlight: Highlight) -> Color {
+        match highlight {
+            Highlight::Active => self.active,
+            Highlight::Frontier => self.frontier,
+            Highlight::Trail => self.trail,
+        }
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("a theme is always valid TOML")
+    }
+
+    /// Loads a theme from a TOML file. A theme without a name is named after the file.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut theme = Self::from_toml(&fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        if theme.name.is_empty() {
+            if let Some(stem) = path.file_stem() {
+                theme.name = stem.to_string_lossy().into_owned();
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+/// Serializes colors as `"#rrggbb"`, with an alpha byte only when they are not opaque.
+mod hex {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut hex = format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b);
+        if color.a != 255 {
+            hex += &format!("{:02x}", color.a);
+        }
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        parse(&hex).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "expected a color like \"#rrggbb\" or \"#rrggbbaa\", found {hex:?}"
+            ))
+        })
+    }
+
+    fn parse(hex: &str) -> Option<Color> {
+        let digits = hex.strip_prefix('#')?;
+        if !matches!(digits.len(), 6 | 8) || !digits.is_ascii() {
+            return None;
+        }
+
+        let byte = |i: usize| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok();
+        let alpha = if digits.len() == 8 { byte(6)? } else { 255 };
+        Some(Color::new(byte(0)?, byte(2)?, byte(4)?, alpha))
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{helpers::Pos, maze::Maze};
+
+/// The most snapshots a timeline keeps before it thins them out.
+const MAX_CHECKPOINTS: usize = 64;
+
+/// The fewest snapshots a timeline keeps however large its maze is: the start and one more.
+const MIN_CHECKPOINTS: usize = 2;
+
+/// How many grid cells a timeline's snapshots may hold between them by default, which keeps
+/// them to a few hundred megabytes for any size of maze.
+pub const CHECKPOINT_CELLS: usize = 1 << 22;
+
+/// A maze along with the history of its generation, so it can be stepped backwards and sought
+/// to any step it has been through.
+///
+/// Generators are deterministic for a given seed, so instead of undoing steps the timeline
+/// keeps snapshots of the generator every so many steps and replays from the closest one. The
+/// snapshots get further apart as the history grows, so memory stays bounded no matter how
+/// many steps a generator takes.
+///
+/// Every snapshot is a whole copy of the generator, with its grid and working state, so it
+/// costs memory in proportion to the number of cells. A timeline keeps as many snapshots as
+/// fit in a budget of cells, between 2 and 64, so the history of a large maze takes about as
+/// much memory as that of a small one but has to replay further to seek.
+///
+/// It also collects the cells the steps it runs change, from their events, until they are
+/// taken with `take_changes`.
+#[derive(Clone)]
+pub struct Timeline<M> {
+    maze: M,
+    position: usize,
+    recorded: usize,
+    /// `checkpoints[i]` is the maze after `i * interval` steps.
+    checkpoints: Vec<M>,
+    interval: usize,
+    /// How many snapshots fit in `checkpoint_cells`.
+    max_checkpoints: usize,
+    checkpoint_cells: usize,
+    /// The cells changed since the last `take_changes`, or `None` if it could be any of them.
+    changes: Option<Vec<Pos>>,
+}
+
+impl<M: Maze + Clone> Timeline<M> {
+    /// Starts recording from the current state of `maze` as step 0, with snapshots of up to
+    /// `CHECKPOINT_CELLS` cells between them.
+    pub fn new(maze: M) -> Self {
+        Self::with_checkpoint_cells(maze, CHECKPOINT_CELLS)
+    }
+
+    /// Starts recording like `new`, with snapshots of up to `checkpoint_cells` cells between
+    /// them, for when several timelines share the memory.
+    pub fn with_checkpoint_cells(maze: M, checkpoint_cells: usize) -> Self {
+        let nodes = maze.nodes();
+        let cells = (nodes.len() * nodes.first().map_or(0, Vec::len)).max(1);
+
+        Self {
+            checkpoints: vec![maze.clone()],
+            maze,
+            position: 0,
+            recorded: 0,
+            interval: MAX_CHECKPOINTS,
+            max_checkpoints: (checkpoint_cells / cells).clamp(MIN_CHECKPOINTS, MAX_CHECKPOINTS),
+            checkpoint_cells,
+            changes: None,
+        }
+    }
+
+    pub fn maze(&self) -> &M {
+        &self.maze
+    }
+
+    /// The number of steps that led to the current state of the maze.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The furthest step the maze has been generated to.
+    pub fn recorded(&self) -> usize {
+        self.recorded
+    }
+
+    /// How many snapshots the timeline holds.
+    pub fn checkpoints(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    /// Resets the maze with a random seed and forgets the history.
+    pub fn reset(&mut self) {
+        self.maze.reset();
+        *self = Self::with_checkpoint_cells(self.maze.clone(), self.checkpoint_cells);
+    }
+
+    pub fn reset_with_seed(&mut self, seed: u64) {
+        self.maze.reset_with_seed(seed);
+        *self = Self::with_checkpoint_cells(self.maze.clone(), self.checkpoint_cells);
+    }
+
+    /// Runs one step, unless the maze is already complete. Returns whether it did.
+    pub fn step_forward(&mut self) -> bool {
+        if self.maze.complete() {
+            return false;
+        }
+
+        let cells = self.cells();
+        let changes = &mut self.changes;
+        self.maze.step(&mut |event| {
+            if let Some(list) = changes {
+                list.extend(event.cells());
+                // Past this point repainting everything is cheaper.
+                if list.len() > cells {
+                    *changes = None;
+                }
+            }
+        });
+        self.position += 1;
+
+        if self.position > self.recorded {
+            self.recorded = self.position;
+            if self.position.is_multiple_of(self.interval) {
+                self.checkpoints.push(self.maze.clone());
+                if self.checkpoints.len() > self.max_checkpoints {
+                    self.thin_checkpoints();
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Goes back to the state before the last step, unless this is the start. Returns whether
+    /// it did.
+    pub fn step_back(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+
+        self.seek(self.position - 1);
+        true
+    }
+
+    /// Moves to the state after `position` steps, or the furthest one recorded if that is
+    /// earlier.
+    pub fn seek(&mut self, position: usize) {
+        let position = position.min(self.recorded);
+        self.changes = None;
+
+        // Replaying from the current state is cheapest when going forwards a little.
+        let checkpoint = position / self.interval;
+        if position < self.position || checkpoint * self.interval > self.position {
+            self.maze = self.checkpoints[checkpoint].clone();
+            self.position = checkpoint * self.interval;
+        }
+
+        while self.position < position {
+            self.maze.generate();
+            self.position += 1;
+        }
+    }
+
+    /// The cells that changed since this was last called, in no particular order and possibly
+    /// more than once, or `None` if any of them may have, like after seeking or a reset.
+    pub fn take_changes(&mut self) -> Option<Vec<Pos>> {
+        self.changes.replace(vec![])
+    }
+
+    fn cells(&self) -> usize {
+        let nodes = self.maze.nodes();
+        nodes.len() * nodes.first().map_or(0, Vec::len)
+    }
+
+    /// Drops every other checkpoint and doubles the interval between them.
+    fn thin_checkpoints(&mut self) {
+        let mut index = 0;
+        self.checkpoints.retain(|_| {
+            index += 1;
+            index % 2 == 1
+        });
+        self.interval *= 2;
+    }
+}
@@ -0,0 +1,256 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{helpers::Node, maze::Maze, validate::validate};
+
+/// The result of comparing how often a generator produced each spanning tree of a small
+/// grid against a uniform distribution.
+#[derive(Clone, Debug)]
+pub struct UniformityReport {
+    pub width: usize,
+    pub height: usize,
+    /// The number of distinct spanning trees the grid has.
+    pub trees: usize,
+    pub samples: usize,
+    /// Trees that did not come up once in `samples` mazes.
+    pub unseen: usize,
+    /// Generated mazes that were not spanning trees at all.
+    pub invalid: usize,
+    pub chi_squared: f64,
+    pub degrees_of_freedom: usize,
+    /// The probability of a uniform generator scoring at least `chi_squared`.
+    pub p_value: f64,
+}
+
+impl UniformityReport {
+    /// Whether the observed distribution is consistent with a uniform one at the given
+    /// significance level.
+    pub fn is_uniform(&self, significance: f64) -> bool {
+        self.invalid == 0 && self.p_value >= significance
+    }
+}
+
+impl fmt::Display for UniformityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} samples over the {} spanning trees of a {}x{} grid: chi² = {:.2} ({} df), p = {:.4}, {} unseen, {} invalid",
+            self.samples,
+            self.trees,
+            self.width,
+            self.height,
+            self.chi_squared,
+            self.degrees_of_freedom,
+            self.p_value,
+            self.unseen,
+            self.invalid
+        )
+    }
+}
+
+/// Generates `samples` mazes of `width` by `height` with the generator `new` makes, seeding
+/// them with consecutive seeds starting at `seed`, and runs a chi-squared goodness of fit test of how often each
+/// spanning tree came up against a uniform distribution.
+///
+/// Every spanning tree of the grid is enumerated, so this is only feasible for tiny grids:
+/// a 3x3 grid has 192 of them, a 4x4 grid already has 100,352.
+pub fn test_uniformity<M: Maze>(
+    new: impl FnOnce(usize, usize) -> M,
+    width: usize,
+    height: usize,
+    samples: usize,
+    seed: u64,
+) -> UniformityReport {
+    let trees = spanning_trees(width, height);
+    let index: HashMap<Vec<bool>, usize> = trees
+        .iter()
+        .enumerate()
+        .map(|(i, tree)| (passages(tree), i))
+        .collect();
+
+    let mut counts = vec![0; trees.len()];
+    let mut invalid = 0;
+
+    let mut maze = new(width, height);
+    for i in 0..samples {
+        maze.reset_with_seed(seed.wrapping_add(i as u64));
+        maze.finish();
+
+        match index.get(&passages(maze.nodes())) {
+            Some(&i) => counts[i] += 1,
+            None => invalid += 1,
+        }
+    }
+
+    let expected = samples as f64 / trees.len() as f64;
+    let chi_squared = counts
+        .iter()
+        .map(|&count| (count as f64 - expected).powi(2) / expected)
+        .sum();
+    let degrees_of_freedom = trees.len() - 1;
+
+    UniformityReport {
+        width,
+        height,
+        trees: trees.len(),
+        samples,
+        unseen: counts.iter().filter(|&&count| count == 0).count(),
+        invalid,
+        chi_squared,
+        degrees_of_freedom,
+        p_value: chi_squared_p_value(chi_squared, degrees_of_freedom),
+    }
+}
+
+/// Every spanning tree of a `width` by `height` grid, in no particular order.
+///
+/// # Panics
+///
+/// If the grid has 64 or more interior walls, as subsets of them are enumerated as bitmasks.
+pub fn spanning_trees(width: usize, height: usize) -> Vec<Vec<Vec<Node>>> {
+    let walls = interior_walls(width, height);
+    assert!(
+        walls.len() < 64,
+        "a {width}x{height} grid is too large to enumerate its spanning trees"
+    );
+
+    // A spanning tree opens exactly `cells - 1` walls, so only subsets of that size are
+    // carved. The validator rejects the ones that contain a cycle.
+    let tree_size = (width * height).saturating_sub(1) as u32;
+    (0..1u64 << walls.len())
+        .filter(|mask| mask.count_ones() == tree_size)
+        .map(|mask| {
+            let open: Vec<bool> = (0..walls.len()).map(|i| mask & (1 << i) != 0).collect();
+            carve(&walls, &open, width, height)
+        })
+        .filter(|nodes| validate(nodes).is_ok())
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum Wall {
+    Up(usize, usize),
+    Left(usize, usize),
+}
+
+fn interior_walls(width: usize, height: usize) -> Vec<Wall> {
+    let mut walls = vec![];
+    for x in 0..width {
+        for y in 0..height {
+            if y > 0 {
+                walls.push(Wall::Up(x, y));
+            }
+            if x > 0 {
+                walls.push(Wall::Left(x, y));
+            }
+        }
+    }
+    walls
+}
+
+fn carve(walls: &[Wall], open: &[bool], width: usize, height: usize) -> Vec<Vec<Node>> {
+    let mut nodes = vec![vec![Node::new(); height]; width];
+    for (wall, &open) in walls.iter().zip(open) {
+        if open {
+            match *wall {
+                Wall::Up(x, y) => nodes[x][y].up = false,
+                Wall::Left(x, y) => nodes[x][y].left = false,
+            }
+        }
+    }
+    nodes
+}
+
+/// Which interior walls of `nodes` are open, in the order of `interior_walls`.
+fn passages(nodes: &[Vec<Node>]) -> Vec<bool> {
+    let width = nodes.len();
+    let height = nodes.first().map_or(0, Vec::len);
+
+    interior_walls(width, height)
+        .into_iter()
+        .map(|wall| match wall {
+            Wall::Up(x, y) => !nodes[x][y].up,
+            Wall::Left(x, y) => !nodes[x][y].left,
+        })
+        .collect()
+}
+
+/// The probability of a chi-squared distributed variable with `degrees_of_freedom` degrees of
+/// freedom being at least `chi_squared`.
+fn chi_squared_p_value(chi_squared: f64, degrees_of_freedom: usize) -> f64 {
+    if degrees_of_freedom == 0 {
+        return 1.0;
+    }
+
+    upper_regularized_gamma(degrees_of_freedom as f64 / 2.0, chi_squared / 2.0)
+}
+
+/// `Q(a, x)`, computed with a power series for small `x` and a continued fraction otherwise.
+fn upper_regularized_gamma(a: f64, x: f64) -> f64 {
+    const ITERATIONS: usize = 500;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    if x <= 0.0 {
+        return 1.0;
+    }
+
+    let prefactor = (a * x.ln() - x - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        for n in 1..ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        return (1.0 - sum * prefactor).clamp(0.0, 1.0);
+    }
+
+    // Lentz's method for the continued fraction of Q(a, x).
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for n in 1..ITERATIONS {
+        let an = -(n as f64) * (n as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    (prefactor * h).clamp(0.0, 1.0)
+}
+
+/// The natural logarithm of the gamma function, using the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut series = 1.000_000_000_190_015;
+    for (i, coefficient) in COEFFICIENTS.iter().enumerate() {
+        series += coefficient / (x + 1.0 + i as f64);
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
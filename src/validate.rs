@@ -0,0 +1,107 @@
+use std::{error::Error, fmt};
+
+use crate::helpers::{Node, Pos};
+
+/// The ways a grid can fail to be a perfect maze.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValidationError {
+    /// The grid has no cells.
+    Empty,
+    /// A column has a different height than the first one.
+    Ragged { x: usize },
+    /// A cell on the top or left edge has a passage leading out of the grid.
+    OpenBoundary(Pos),
+    /// A spanning tree of `cells` cells has exactly `cells - 1` passages.
+    PassageCount { expected: usize, found: usize },
+    /// The passages form a loop that passes through this cell.
+    Cycle(Pos),
+    /// This cell cannot be reached from the top left cell.
+    Unreachable(Pos),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Empty => write!(f, "the grid has no cells"),
+            ValidationError::Ragged { x } => {
+                write!(f, "column {x} has a different height than column 0")
+            }
+            ValidationError::OpenBoundary(pos) => {
+                write!(
+                    f,
+                    "({}, {}) has a passage through the boundary",
+                    pos.x, pos.y
+                )
+            }
+            ValidationError::PassageCount { expected, found } => {
+                write!(f, "expected {expected} passages, found {found}")
+            }
+            ValidationError::Cycle(pos) => write!(f, "({}, {}) is part of a cycle", pos.x, pos.y),
+            ValidationError::Unreachable(pos) => {
+                write!(f, "({}, {}) is not reachable from (0, 0)", pos.x, pos.y)
+            }
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+/// Checks that `nodes` is a perfect maze: its passages form a spanning tree of the grid,
+/// so every cell is reachable from every other cell along exactly one path.
+pub fn validate(nodes: &[Vec<Node>]) -> Result<(), ValidationError> {
+    let width = nodes.len();
+    let height = nodes.first().map_or(0, Vec::len);
+
+    if width == 0 || height == 0 {
+        return Err(ValidationError::Empty);
+    }
+
+    if let Some(x) = nodes.iter().position(|column| column.len() != height) {
+        return Err(ValidationError::Ragged { x });
+    }
+
+    if let Some(x) = nodes.iter().position(|column| !column[0].up) {
+        return Err(ValidationError::OpenBoundary(Pos::new(x, 0)));
+    }
+    if let Some(y) = nodes[0].iter().position(|node| !node.left) {
+        return Err(ValidationError::OpenBoundary(Pos::new(0, y)));
+    }
+
+    let found = nodes
+        .iter()
+        .flatten()
+        .map(|node| usize::from(!node.up) + usize::from(!node.left))
+        .sum();
+    let expected = width * height - 1;
+    if found != expected {
+        return Err(ValidationError::PassageCount { expected, found });
+    }
+
+    let mut visited = vec![vec![false; height]; width];
+    let mut stack = vec![(Pos::new(0, 0), None)];
+    visited[0][0] = true;
+
+    while let Some((pos, parent)) = stack.pop() {
+        for neighbor in pos.open_neighbors(nodes) {
+            let next_pos = neighbor.get_pos();
+            if Some(next_pos) == parent {
+                continue;
+            }
+
+            if visited[next_pos.x][next_pos.y] {
+                return Err(ValidationError::Cycle(next_pos));
+            }
+
+            visited[next_pos.x][next_pos.y] = true;
+            stack.push((next_pos, Some(pos)));
+        }
+    }
+
+    for (x, column) in visited.iter().enumerate() {
+        if let Some(y) = column.iter().position(|visited| !visited) {
+            return Err(ValidationError::Unreachable(Pos::new(x, y)));
+        }
+    }
+
+    Ok(())
+}
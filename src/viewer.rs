@@ -0,0 +1,533 @@
+use std::time::{Duration, Instant};
+
+use raylib::prelude::*;
+
+use crate::{
+    config::Playlist,
+    maze::Maze,
+    pace::Pacer,
+    play::{draw_game, Game, Move},
+    render::Renderer,
+    theme::Theme,
+    timeline::Timeline,
+    Algorithm,
+};
+
+/// The height of the timeline slider along the bottom of the window, in pixels.
+const SLIDER_HEIGHT: i32 = 12;
+
+/// How far the speed can be scaled up or down from each algorithm's own speed.
+const MAX_SPEED_SCALE: f32 = 64.0;
+
+pub(crate) const PANEL_FONT_SIZE: i32 = 20;
+
+/// How long a finished maze stays on screen by default.
+pub const DEFAULT_HOLD: Duration = Duration::from_secs(2);
+
+/// How often the steps per second in the HUD are measured.
+const RATE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The bindings the help overlay lists.
+const BINDINGS: [(&str, &str); 13] = [
+    ("Space", "pause / resume"),
+    ("Left / Right", "step backwards / forwards while paused"),
+    ("Up / Down, wheel", "speed up / slow down"),
+    ("Drag the bottom edge", "scrub through the steps so far"),
+    ("N", "skip to the next algorithm"),
+    ("1 - 9", "pick an algorithm"),
+    ("R", "regenerate with a new seed"),
+    ("P", "play the finished maze"),
+    ("O", "toggle highlights"),
+    ("I", "toggle the HUD"),
+    ("T", "next theme"),
+    ("H / F1", "toggle this help"),
+    ("Esc", "quit"),
+];
+
+/// The bindings the help overlay lists while playing a maze.
+const PLAY_BINDINGS: [(&str, &str); 8] = [
+    ("Arrows, WASD", "move"),
+    ("F", "toggle fog of war"),
+    ("Space", "replay your path against the shortest one"),
+    ("P", "stop playing"),
+    ("O", "toggle highlights"),
+    ("T", "next theme"),
+    ("H / F1", "toggle this help"),
+    ("Esc", "quit"),
+];
+
+/// The keys that move the player, with the direction they move in.
+const MOVE_KEYS: [(KeyboardKey, Move); 8] = [
+    (KeyboardKey::KEY_UP, Move::Up),
+    (KeyboardKey::KEY_W, Move::Up),
+    (KeyboardKey::KEY_DOWN, Move::Down),
+    (KeyboardKey::KEY_S, Move::Down),
+    (KeyboardKey::KEY_LEFT, Move::Left),
+    (KeyboardKey::KEY_A, Move::Left),
+    (KeyboardKey::KEY_RIGHT, Move::Right),
+    (KeyboardKey::KEY_D, Move::Right),
+];
+
+/// What the viewer is doing with the current maze.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Phase {
+    Generating,
+    /// Showing the finished maze for `remaining` more unpaused time before moving on.
+    Holding {
+        remaining: Duration,
+    },
+}
+
+/// Runs the visualizer: generates the current algorithm's maze at a steady number of steps
+/// per second, shows it for `hold` once it is finished, moves on to the next algorithm, and
+/// lets the user control it with the bindings in `BINDINGS`.
+pub struct Viewer {
+    pub current: Algorithm,
+    /// The maze of `current` and its history.
+    pub timeline: Timeline<Box<dyn Maze>>,
+    /// The size of the mazes in cells.
+    width: usize,
+    height: usize,
+    pub renderer: Renderer,
+    /// How long a finished maze stays on screen before the next algorithm starts.
+    pub hold: Duration,
+    /// Picks the algorithm that plays after the current one.
+    pub playlist: Playlist,
+    /// Whether every maze is played as soon as it is finished, instead of after pressing P.
+    pub play: bool,
+    /// The finished maze the user is finding their way through, which stays on screen until
+    /// they stop.
+    game: Option<Game>,
+    /// Whether new games start with fog of war.
+    fog: bool,
+    phase: Phase,
+    pacer: Pacer,
+    last_frame: Instant,
+    themes: Vec<Theme>,
+    theme: usize,
+    paused: bool,
+    scrubbing: bool,
+    show_help: bool,
+    show_hud: bool,
+    /// What the speed each algorithm runs at is multiplied by, always a power of two.
+    speed_scale: f32,
+    started: Instant,
+    /// Steps run since the last time the rate was measured, and when that was.
+    rate_steps: usize,
+    rate_since: Instant,
+    steps_per_second: f32,
+}
+
+impl Viewer {
+    /// Starts on a randomly seeded `width` by `height` maze of `current`. `themes` are what T
+    /// cycles through, starting with the first one, if any.
+    pub fn new(
+        current: Algorithm,
+        width: usize,
+        height: usize,
+        mut renderer: Renderer,
+        themes: Vec<Theme>,
+    ) -> Self {
+        if let Some(theme) = themes.first() {
+            renderer.options.theme = theme.clone();
+        }
+
+        let mut timeline = Timeline::new(current.new_maze(width, height));
+        timeline.reset();
+
+        Self {
+            current,
+            timeline,
+            width,
+            height,
+            renderer,
+            hold: DEFAULT_HOLD,
+            playlist: Playlist::default(),
+            play: false,
+            game: None,
+            fog: false,
+            phase: Phase::Generating,
+            pacer: Pacer::new(),
+            last_frame: Instant::now(),
+            themes,
+            theme: 0,
+            paused: false,
+            scrubbing: false,
+            show_help: false,
+            show_hud: false,
+            speed_scale: 1.0,
+            started: Instant::now(),
+            rate_steps: 0,
+            rate_since: Instant::now(),
+            steps_per_second: 0.0,
+        }
+    }
+
+    /// Advances the maze by the time since the last frame and draws it. `speed` is how many
+    /// steps per second it runs at before the user speeds it up or slows it down.
+    pub fn update(&mut self, speed: usize, rl: &mut RaylibHandle, thread: &RaylibThread) {
+        let now = Instant::now();
+        let elapsed = now - self.last_frame;
+        self.last_frame = now;
+
+        self.handle_input(rl);
+
+        match self.phase {
+            Phase::Generating => {
+                if self.paused {
+                    self.pacer.reset();
+                } else {
+                    let rate = speed as f64 * self.speed_scale as f64;
+                    for _ in 0..self.pacer.advance(elapsed, rate) {
+                        if !self.timeline.step_forward() {
+                            break;
+                        }
+                        self.rate_steps += 1;
+                    }
+                }
+
+                if self.timeline.maze().complete() && !self.paused {
+                    self.finished();
+                    self.phase = Phase::Holding {
+                        remaining: self.hold,
+                    };
+                    if self.play {
+                        self.start_game();
+                    }
+                }
+            }
+            Phase::Holding { remaining } => {
+                if !self.timeline.maze().complete() {
+                    // Stepped or scrubbed back into the generation.
+                    self.phase = Phase::Generating;
+                } else if !self.paused && self.game.is_none() {
+                    match remaining.checked_sub(elapsed) {
+                        Some(remaining) if !remaining.is_zero() => {
+                            self.phase = Phase::Holding { remaining };
+                        }
+                        _ => {
+                            let next = self
+                                .playlist
+                                .next(Some(self.current), &mut rand::thread_rng());
+                            self.switch_to(next);
+                        }
+                    }
+                }
+            }
+        }
+
+        let since = self.rate_since.elapsed();
+        if since >= RATE_INTERVAL {
+            self.steps_per_second = self.rate_steps as f32 / since.as_secs_f32();
+            self.rate_steps = 0;
+            self.rate_since = Instant::now();
+        }
+
+        let changes = self.timeline.take_changes();
+        if let Err(err) = self
+            .renderer
+            .render(rl, thread, self.timeline.maze(), changes.as_deref())
+        {
+            println!("Could not render to a texture, drawing every cell each frame: {err}");
+        }
+
+        if let Some(game) = &mut self.game {
+            game.tick(elapsed);
+        }
+
+        let show_slider = self.game.is_none() && (self.paused || self.over_slider(rl));
+        let mut d = rl.begin_drawing(thread);
+        d.clear_background(self.renderer.options.theme.background);
+        self.renderer
+            .draw(&mut d, self.timeline.maze(), Vector2::zero());
+        if let Some(game) = &self.game {
+            draw_game(&mut d, game, &self.renderer.options);
+            self.draw_score(&mut d, game);
+        } else if self.show_hud {
+            self.draw_hud(&mut d);
+        }
+        if show_slider {
+            self.draw_slider(&mut d);
+        }
+        if self.show_help {
+            let bindings: &[_] = if self.game.is_some() {
+                &PLAY_BINDINGS
+            } else {
+                &BINDINGS
+            };
+            draw_panel(&mut d, &self.renderer.options.theme, bindings, false);
+        }
+    }
+
+    /// Reports how long generating `maze` took and whether it came out valid.
+    fn finished(&self) {
+        let maze = self.timeline.maze();
+        println!("{} took {:?}", self.current, self.started.elapsed());
+
+        if let Err(err) = maze.validate() {
+            println!(
+                "{} generated an invalid maze (seed {}): {err}",
+                self.current,
+                maze.seed()
+            );
+        }
+    }
+
+    /// Starts on a new, randomly seeded maze of `algorithm`.
+    fn switch_to(&mut self, algorithm: Algorithm) {
+        self.timeline = Timeline::new(algorithm.new_maze(self.width, self.height));
+        self.timeline.reset();
+        self.current = algorithm;
+        self.restart();
+    }
+
+    /// Starts the clocks over for a new maze.
+    fn restart(&mut self) {
+        self.phase = Phase::Generating;
+        self.pacer.reset();
+        self.started = Instant::now();
+        self.rate_steps = 0;
+        self.rate_since = Instant::now();
+        self.steps_per_second = 0.0;
+    }
+
+    /// Lets the user find their way through the current maze, if it is finished.
+    fn start_game(&mut self) {
+        let maze = self.timeline.maze();
+        if maze.complete() {
+            let mut game = Game::new(maze.nodes());
+            game.fog = self.fog;
+            self.game = Some(game);
+        }
+    }
+
+    fn handle_input(&mut self, rl: &RaylibHandle) {
+        if rl.is_key_pressed(KeyboardKey::KEY_H) || rl.is_key_pressed(KeyboardKey::KEY_F1) {
+            self.show_help = !self.show_help;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_O) {
+            self.renderer.options.highlights = !self.renderer.options.highlights;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_T) && !self.themes.is_empty() {
+            self.theme = (self.theme + 1) % self.themes.len();
+            self.renderer.options.theme = self.themes[self.theme].clone();
+        }
+
+        if let Some(game) = &mut self.game {
+            for (key, direction) in MOVE_KEYS {
+                if rl.is_key_pressed(key) && game.step(direction) && game.finished() {
+                    println!(
+                        "Reached the exit in {} moves and {:.1}s, the shortest path takes {}",
+                        game.moves(),
+                        game.elapsed().as_secs_f32(),
+                        game.optimal_moves()
+                    );
+                }
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_F) {
+                game.fog = !game.fog;
+                self.fog = game.fog;
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
+                game.replay();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_P) {
+                self.game = None;
+            }
+            return;
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_P) {
+            self.start_game();
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
+            self.paused = !self.paused;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_I) {
+            self.show_hud = !self.show_hud;
+        }
+
+        let wheel = rl.get_mouse_wheel_move();
+        if rl.is_key_pressed(KeyboardKey::KEY_UP) || wheel > 0.0 {
+            self.speed_scale = (self.speed_scale * 2.0).min(MAX_SPEED_SCALE);
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_DOWN) || wheel < 0.0 {
+            self.speed_scale = (self.speed_scale / 2.0).max(1.0 / MAX_SPEED_SCALE);
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_R) {
+            self.timeline.reset();
+            self.restart();
+        }
+
+        let digits = [
+            KeyboardKey::KEY_ONE,
+            KeyboardKey::KEY_TWO,
+            KeyboardKey::KEY_THREE,
+            KeyboardKey::KEY_FOUR,
+            KeyboardKey::KEY_FIVE,
+            KeyboardKey::KEY_SIX,
+            KeyboardKey::KEY_SEVEN,
+            KeyboardKey::KEY_EIGHT,
+            KeyboardKey::KEY_NINE,
+        ];
+        for (key, algorithm) in digits.into_iter().zip(Algorithm::all().iter().copied()) {
+            if rl.is_key_pressed(key) && algorithm != self.current {
+                self.switch_to(algorithm);
+            }
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_N) {
+            let next = self
+                .playlist
+                .next(Some(self.current), &mut rand::thread_rng());
+            self.switch_to(next);
+        }
+
+        if self.paused {
+            if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+                self.timeline.step_forward();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+                self.timeline.step_back();
+            }
+        }
+
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_LEFT_BUTTON) && self.over_slider(rl) {
+            self.scrubbing = true;
+            self.paused = true;
+        }
+        if !rl.is_mouse_button_down(MouseButton::MOUSE_LEFT_BUTTON) {
+            self.scrubbing = false;
+        }
+
+        if self.scrubbing {
+            let fraction = (rl.get_mouse_x() as f32 / rl.get_screen_width() as f32).clamp(0.0, 1.0);
+            self.timeline
+                .seek((fraction * self.timeline.recorded() as f32).round() as usize);
+        }
+    }
+
+    fn over_slider(&self, rl: &RaylibHandle) -> bool {
+        rl.get_mouse_y() >= rl.get_screen_height() - SLIDER_HEIGHT
+    }
+
+    /// Draws how far into the recorded steps the maze is along the bottom of the window.
+    fn draw_slider(&self, d: &mut RaylibDrawHandle) {
+        let timeline = &self.timeline;
+        let width = d.get_screen_width();
+        let top = d.get_screen_height() - SLIDER_HEIGHT;
+        let theme = &self.renderer.options.theme;
+
+        let fraction = if timeline.recorded() == 0 {
+            0.0
+        } else {
+            timeline.position() as f32 / timeline.recorded() as f32
+        };
+        let filled = (fraction * width as f32) as i32;
+
+        d.draw_rectangle(0, top, width, SLIDER_HEIGHT, theme.background.fade(0.75));
+        d.draw_rectangle(0, top, filled, SLIDER_HEIGHT, theme.active.fade(0.75));
+        d.draw_rectangle(filled - 2, top, 4, SLIDER_HEIGHT, theme.passage);
+    }
+
+    /// Shows how the user is doing in the top right corner.
+    fn draw_score(&self, d: &mut RaylibDrawHandle, game: &Game) {
+        let moves = game.moves().to_string();
+        let optimal = game.optimal_moves().to_string();
+        let elapsed = format!("{:.1}s", game.elapsed().as_secs_f32());
+        let fog = if game.fog { "on" } else { "off" };
+        let status = if game.finished() {
+            "done, Space to replay"
+        } else {
+            "find the exit"
+        };
+
+        let rows = [
+            ("Moves", moves.as_str()),
+            ("Shortest", optimal.as_str()),
+            ("Time", elapsed.as_str()),
+            ("Fog", fog),
+            ("Status", status),
+        ];
+        draw_panel(d, &self.renderer.options.theme, &rows, true);
+    }
+
+    /// Shows what is being generated, how fast and in which theme in the top right corner.
+    fn draw_hud(&self, d: &mut RaylibDrawHandle) {
+        let timeline = &self.timeline;
+        let maze = timeline.maze();
+        let nodes = maze.nodes();
+        let cells = nodes.len() * nodes.first().map_or(0, Vec::len);
+        let visited = if cells == 0 {
+            0.0
+        } else {
+            maze.visited_cells() as f32 / cells as f32 * 100.0
+        };
+
+        let algorithm = self.current.to_string();
+        let seed = maze.seed().to_string();
+        let steps = timeline.position().to_string();
+        let visited = format!("{visited:.1}%");
+        let elapsed = format!("{:.1}s", self.started.elapsed().as_secs_f32());
+        let rate = format!("{:.0}", self.steps_per_second);
+
+        let rows = [
+            ("Algorithm", algorithm.as_str()),
+            ("Seed", seed.as_str()),
+            ("Steps", steps.as_str()),
+            ("Visited", visited.as_str()),
+            ("Elapsed", elapsed.as_str()),
+            ("Steps/s", rate.as_str()),
+            ("Theme", self.renderer.options.theme.name.as_str()),
+        ];
+        draw_panel(d, &self.renderer.options.theme, &rows, true);
+    }
+}
+
+/// Lists `rows` as two columns in a panel in the top left or top right corner.
+pub(crate) fn draw_panel(
+    d: &mut RaylibDrawHandle,
+    theme: &Theme,
+    rows: &[(&str, &str)],
+    right: bool,
+) {
+    let padding = 10;
+    let line_height = PANEL_FONT_SIZE + 4;
+
+    let key_width = rows
+        .iter()
+        .map(|(key, _)| measure_text(key, PANEL_FONT_SIZE))
+        .max()
+        .unwrap_or(0);
+    let value_width = rows
+        .iter()
+        .map(|(_, value)| measure_text(value, PANEL_FONT_SIZE))
+        .max()
+        .unwrap_or(0);
+
+    let width = key_width + value_width + 3 * padding;
+    let left = if right {
+        d.get_screen_width() - width - padding
+    } else {
+        padding
+    };
+
+    d.draw_rectangle(
+        left,
+        padding,
+        width,
+        rows.len() as i32 * line_height + 2 * padding,
+        theme.background.fade(0.85),
+    );
+    for (i, (key, value)) in rows.iter().enumerate() {
+        let y = 2 * padding + i as i32 * line_height;
+        d.draw_text(key, left + padding, y, PANEL_FONT_SIZE, theme.active);
+        d.draw_text(
+            value,
+            left + key_width + 2 * padding,
+            y,
+            PANEL_FONT_SIZE,
+            theme.passage,
+        );
+    }
+}
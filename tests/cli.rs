@@ -0,0 +1,106 @@
+use std::{env, fs};
+
+use clap::Parser;
+use rust_mazes::{
+    cli::{self, Cli, Command, Format, GenerateArgs},
+    formats::SavedMaze,
+    helpers::Pos,
+    Algorithm,
+};
+
+fn named(id: &str) -> Algorithm {
+    Algorithm::named(id).unwrap()
+}
+
+#[test]
+fn opens_the_viewer_without_a_subcommand() {
+    let cli = Cli::try_parse_from(["maze", "--algorithm", "prim", "--seed", "7"]).unwrap();
+    assert!(cli.command.is_none());
+    assert_eq!(cli.view.algorithm, Some(named("prim")));
+    assert_eq!(cli.view.seed, Some(7));
+}
+
+#[test]
+fn parses_subcommands() {
+    let cli = Cli::try_parse_from([
+        "maze",
+        "generate",
+        "-a",
+        "aldous-broder",
+        "--width",
+        "8",
+        "-o",
+        "maze.svg",
+    ])
+    .unwrap();
+    let Some(Command::Generate(args)) = cli.command else {
+        panic!("expected generate, got {:?}", cli.command);
+    };
+    assert_eq!(args.algorithm, Some(named("aldous-broder")));
+    assert_eq!(args.width, 8);
+    assert_eq!(args.height, 20);
+
+    let cli = Cli::try_parse_from(["maze", "solve", "maze.json", "--from", "1,2"]).unwrap();
+    let Some(Command::Solve(args)) = cli.command else {
+        panic!("expected solve, got {:?}", cli.command);
+    };
+    assert_eq!(args.from, Some(Pos::new(1, 2)));
+
+    assert!(Cli::try_parse_from(["maze", "solve", "maze.json", "--to", "3"]).is_err());
+    assert!(Cli::try_parse_from(["maze", "bench", "-a", "bogo"]).is_err());
+}
+
+#[test]
+fn rejects_empty_mazes_and_stopped_speeds() {
+    for args in [
+        &["maze", "--width", "0"][..],
+        &["maze", "generate", "--width", "0", "--height", "5"],
+        &["maze", "stats", "--height", "0"],
+        &["maze", "bench", "--width", "0"],
+        &["maze", "compare", "-a", "prim", "--height", "0"],
+        &["maze", "--speed", "0"],
+        &["maze", "compare", "-a", "prim", "--speed", "0"],
+    ] {
+        assert!(Cli::try_parse_from(args).is_err(), "{args:?}");
+    }
+    assert!(Cli::try_parse_from(["maze", "generate", "--width", "1"]).is_ok());
+}
+
+#[test]
+fn guesses_formats_from_extensions() {
+    assert_eq!(Format::from_path("a/maze.PNG".as_ref()), Some(Format::Png));
+    assert_eq!(Format::from_path("maze.txt".as_ref()), Some(Format::Ascii));
+    assert_eq!(Format::from_path("maze".as_ref()), None);
+}
+
+#[test]
+fn generated_mazes_load_back() {
+    let dir = env::temp_dir().join(format!("rust_mazes_cli_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    for extension in ["json", "txt", "png"] {
+        let output = dir.join(format!("maze.{extension}"));
+        cli::generate(&GenerateArgs {
+            algorithm: Some(named("hunt-and-kill")),
+            width: 9,
+            height: 5,
+            seed: Some(3),
+            output: Some(output.clone()),
+            format: None,
+            theme: None,
+        })
+        .unwrap();
+
+        let mut expected = named("hunt-and-kill").new_maze(9, 5);
+        expected.reset_with_seed(3);
+        expected.finish();
+
+        let loaded = cli::load_maze(&output).unwrap();
+        assert_eq!(loaded.nodes, expected.nodes(), "{extension}");
+    }
+
+    let saved = SavedMaze::load(dir.join("maze.json")).unwrap();
+    assert_eq!(saved.seed, Some(3));
+
+    fs::remove_dir_all(dir).unwrap();
+}
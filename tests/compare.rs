@@ -0,0 +1,96 @@
+use clap::Parser;
+use rust_mazes::{
+    cli::{Cli, Command},
+    Algorithm,
+};
+#[cfg(feature = "viewer")]
+use rust_mazes::{
+    compare::{layout, Comparison, GAP, LABEL_HEIGHT},
+    render::RenderOptions,
+};
+
+fn named(id: &str) -> Algorithm {
+    Algorithm::named(id).unwrap()
+}
+
+#[test]
+#[cfg(feature = "viewer")]
+fn lays_viewports_out_in_a_near_square_grid() {
+    let size = (100, 50);
+    let cell = (size.0 + GAP, LABEL_HEIGHT + size.1 + GAP);
+
+    let (origins, total) = layout(2, size);
+    assert_eq!(
+        origins,
+        vec![
+            (GAP, GAP + LABEL_HEIGHT),
+            (GAP + cell.0, GAP + LABEL_HEIGHT)
+        ]
+    );
+    assert_eq!(total, (GAP + 2 * cell.0, GAP + cell.1));
+
+    let (origins, total) = layout(5, size);
+    assert_eq!(origins.len(), 5);
+    assert_eq!(origins[3], (GAP, GAP + cell.1 + LABEL_HEIGHT));
+    assert_eq!(total, (GAP + 3 * cell.0, GAP + 2 * cell.1));
+
+    // No two viewports overlap, and all of them fit.
+    for (i, a) in origins.iter().enumerate() {
+        assert!(a.0 + size.0 <= total.0 && a.1 + size.1 <= total.1);
+        for b in &origins[i + 1..] {
+            assert!(a.0 + size.0 <= b.0 || b.0 + size.0 <= a.0 || a.1 + size.1 <= b.1);
+        }
+    }
+
+    assert_eq!(layout(0, size), (vec![], (0, 0)));
+}
+
+#[test]
+#[cfg(feature = "viewer")]
+fn steps_every_viewport_in_lockstep() {
+    let algorithms = [named("prim"), named("kruskal"), named("prim")];
+    let mut comparison = Comparison::new(&algorithms, 6, 4, RenderOptions::default());
+    comparison.reset_with_seed(3);
+
+    let seeds: Vec<u64> = comparison
+        .viewports
+        .iter()
+        .map(|viewport| viewport.timeline.maze().seed())
+        .collect();
+    assert_eq!(seeds, [3, 4, 5]);
+
+    for _ in 0..5 {
+        assert!(comparison.step_forward());
+    }
+    for viewport in &comparison.viewports {
+        assert_eq!(viewport.timeline.position(), 5);
+    }
+
+    while comparison.step_forward() {}
+    assert!(comparison.complete());
+    let finished: Vec<usize> = comparison
+        .viewports
+        .iter()
+        .map(|viewport| viewport.timeline.position())
+        .collect();
+
+    // Stepping back only rewinds the mazes that were still running, keeping them in step.
+    let longest = *finished.iter().max().unwrap();
+    assert!(comparison.step_back());
+    for (viewport, &steps) in comparison.viewports.iter().zip(&finished) {
+        let expected = if steps == longest { steps - 1 } else { steps };
+        assert_eq!(viewport.timeline.position(), expected);
+    }
+}
+
+#[test]
+fn parses_repeated_algorithms() {
+    let cli = Cli::try_parse_from(["maze", "compare", "-a", "prim", "-a", "kruskal"]).unwrap();
+    let Some(Command::Compare(args)) = cli.command else {
+        panic!("expected compare, got {:?}", cli.command);
+    };
+    assert_eq!(args.algorithms, [named("prim"), named("kruskal")]);
+    assert_eq!(args.speed(), named("prim").default_speed());
+
+    assert!(Cli::try_parse_from(["maze", "compare"]).is_err());
+}
@@ -0,0 +1,197 @@
+use std::{collections::HashSet, env, fs};
+
+use clap::Parser;
+use rand::{rngs::StdRng, SeedableRng};
+use rust_mazes::{
+    cli::Cli,
+    config::{Config, Order, Playlist},
+    Algorithm,
+};
+
+fn named(id: &str) -> Algorithm {
+    Algorithm::named(id).unwrap()
+}
+
+const EXAMPLE: &str = r#"
+width = 96
+height = 51
+cell_size = 20.0
+wall_thickness = 2.0
+inset = 1.0
+roundness = 0.5
+anti_alias = true
+theme = "dark"
+hold = 5.0
+
+[speeds]
+aldous-broder = 40000
+
+[playlist]
+order = "weighted"
+algorithms = ["prim", "kruskal", "aldous-broder"]
+weights = { prim = 3 }
+"#;
+
+#[test]
+fn reads_every_setting() {
+    let config = Config::from_toml(EXAMPLE).unwrap();
+    assert_eq!((config.width, config.height), (96, 51));
+    assert_eq!(config.cell_size, 20.0);
+    let options = config.render_options();
+    assert_eq!(options.cell_size, 20.0);
+    assert_eq!(options.wall_thickness, 2.0);
+    assert_eq!(options.inset, 1.0);
+    assert_eq!(options.roundness, 0.5);
+    assert!(options.anti_alias);
+    assert_eq!(config.theme().unwrap().unwrap().name, "dark");
+    assert_eq!(config.hold, 5.0);
+    assert_eq!(config.speed(named("aldous-broder")), 40_000);
+    assert_eq!(config.speed(named("prim")), named("prim").default_speed());
+    assert_eq!(config.playlist.order, Order::Weighted);
+    assert_eq!(config.playlist.weight(named("prim")), 3.0);
+    assert_eq!(config.playlist.weight(named("kruskal")), 1.0);
+
+    assert_eq!(Config::from_toml(&config.to_toml()).unwrap(), config);
+}
+
+#[test]
+fn missing_settings_keep_their_defaults() {
+    assert_eq!(Config::from_toml("").unwrap(), Config::default());
+
+    let config = Config::from_toml("hold = 0.5").unwrap();
+    assert_eq!(config.playlist, Playlist::default());
+    assert_eq!(config.width, Config::default().width);
+}
+
+#[test]
+fn rejects_invalid_configs() {
+    for text in [
+        "widht = 10",
+        "width = 0",
+        "height = 0",
+        "hold = -1.0",
+        "cell_size = 0.0",
+        "cell_size = -4.0",
+        "cell_size = nan",
+        "wall_thickness = -1.0",
+        "inset = inf",
+        "roundness = 1.5",
+        "[playlist]\nweights = { prim = nan }",
+        "[playlist]\nweights = { prim = inf }",
+        "hold = inf",
+        "hold = nan",
+        "hold = 1e300",
+        "[speeds]\nbogosort = 10",
+        "[speeds]\nprim = 0",
+        "[playlist]\nalgorithms = []",
+        "[playlist]\nweights = { prim = -1 }",
+        "[playlist]\norder = \"weighted\"\nalgorithms = [\"prim\"]\nweights = { prim = 0 }",
+    ] {
+        assert!(Config::from_toml(text).is_err(), "{text}");
+    }
+}
+
+#[test]
+fn sequential_playlists_cycle_in_order() {
+    let mut playlist = Playlist::new(
+        Order::Sequential,
+        vec![named("kruskal"), named("binary-tree")],
+    );
+    let mut rng = StdRng::seed_from_u64(0);
+
+    assert_eq!(playlist.next(None, &mut rng), named("kruskal"));
+    assert_eq!(
+        playlist.next(Some(named("kruskal")), &mut rng),
+        named("binary-tree")
+    );
+    assert_eq!(
+        playlist.next(Some(named("binary-tree")), &mut rng),
+        named("kruskal")
+    );
+    // Picked by hand, outside of the playlist.
+    assert_eq!(
+        playlist.next(Some(named("prim")), &mut rng),
+        named("kruskal")
+    );
+}
+
+#[test]
+fn sequential_playlists_play_repeated_algorithms_in_turn() {
+    let order = ["prim", "kruskal", "prim", "binary-tree"].map(named);
+    let mut playlist = Playlist::new(Order::Sequential, order.to_vec());
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let mut current = None;
+    for expected in order.iter().chain(&order) {
+        let next = playlist.next(current, &mut rng);
+        assert_eq!(next, *expected);
+        current = Some(next);
+    }
+}
+
+#[test]
+fn shuffled_playlists_play_everything_once_per_round() {
+    let mut playlist = Playlist::new(Order::Shuffled, Algorithm::all().to_vec());
+    let mut rng = StdRng::seed_from_u64(1);
+
+    let mut current = None;
+    for _ in 0..20 {
+        let mut round = HashSet::new();
+        for _ in 0..Algorithm::all().len() {
+            let next = playlist.next(current, &mut rng);
+            assert_ne!(Some(next), current);
+            assert!(round.insert(next));
+            current = Some(next);
+        }
+    }
+}
+
+#[test]
+fn weighted_playlists_follow_their_weights() {
+    let mut playlist = Config::from_toml(
+        r#"
+[playlist]
+order = "weighted"
+algorithms = ["prim", "kruskal", "binary-tree"]
+weights = { prim = 3, binary-tree = 0 }
+"#,
+    )
+    .unwrap()
+    .playlist;
+    let mut rng = StdRng::seed_from_u64(2);
+
+    let picks: Vec<Algorithm> = (0..4000).map(|_| playlist.next(None, &mut rng)).collect();
+    let prim = picks.iter().filter(|&&a| a == named("prim")).count();
+    let kruskal = picks.iter().filter(|&&a| a == named("kruskal")).count();
+
+    assert_eq!(prim + kruskal, picks.len());
+    assert!((2700..3300).contains(&prim), "picked Prim {prim} times");
+}
+
+#[test]
+fn command_line_options_override_the_config_file() {
+    let dir = env::temp_dir().join(format!("rust_mazes_config_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("mazes.toml");
+    fs::write(&path, EXAMPLE).unwrap();
+
+    let cli = Cli::try_parse_from([
+        "maze",
+        "--config",
+        path.to_str().unwrap(),
+        "--width",
+        "30",
+        "--speed",
+        "100",
+    ])
+    .unwrap();
+    let config = cli.view.config().unwrap();
+
+    assert_eq!((config.width, config.height), (30, 51));
+    for &algorithm in Algorithm::all() {
+        assert_eq!(config.speed(algorithm), 100);
+    }
+    assert_eq!(config.playlist.order, Order::Weighted);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,182 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use rust_mazes::{
+    events::Event,
+    helpers::{Node, Pos},
+    maze::Maze,
+    mazes::*,
+    theme::Highlight,
+};
+
+const SIZES: [(usize, usize); 4] = [(1, 1), (1, 6), (5, 4), (12, 9)];
+const SEEDS: u64 = 10;
+
+/// Removes the wall between two neighboring cells, failing if it is already gone.
+fn carve(nodes: &mut [Vec<Node>], from: Pos, to: Pos) {
+    let (first, second) = (from.min(to), from.max(to));
+    let wall = if first.x == second.x && first.y + 1 == second.y {
+        &mut nodes[second.x][second.y].up
+    } else if first.y == second.y && first.x + 1 == second.x {
+        &mut nodes[second.x][second.y].left
+    } else {
+        panic!("carved between ({from:?}) and ({to:?}), which are not neighbors");
+    };
+
+    assert!(*wall, "carved the wall between {from:?} and {to:?} twice");
+    *wall = false;
+}
+
+/// Every cell that is highlighted, and how, checking `highlight` agrees for every cell.
+fn highlights<M: Maze>(maze: &M) -> HashMap<Pos, Highlight> {
+    let highlights: HashMap<Pos, Highlight> = maze.highlights().into_iter().collect();
+    for (x, column) in maze.nodes().iter().enumerate() {
+        for y in 0..column.len() {
+            let pos = Pos::new(x, y);
+            assert_eq!(
+                maze.highlight(pos),
+                highlights.get(&pos).copied(),
+                "{pos:?}"
+            );
+        }
+    }
+    highlights
+}
+
+/// Replays every event on a copy of the freshly reset maze and checks it ends up identical to
+/// the maze the generator built, and that the events name every cell whose highlight or
+/// visited state changed.
+fn check_replay<M: Maze>(new: fn(usize, usize) -> M, name: &str) {
+    for (width, height) in SIZES {
+        let mut maze = new(width, height);
+
+        for seed in 0..SEEDS {
+            maze.reset_with_seed(seed);
+
+            let mut nodes = maze.nodes().to_vec();
+            let mut visited: HashSet<Pos> = (0..width)
+                .flat_map(|x| (0..height).map(move |y| Pos::new(x, y)))
+                .filter(|&pos| maze.is_visited(pos))
+                .collect();
+
+            while !maze.complete() {
+                let before = highlights(&maze);
+                let events = maze.step_events();
+                let after = highlights(&maze);
+
+                let named: HashSet<Pos> = events.iter().flat_map(|event| event.cells()).collect();
+                for pos in before.keys().chain(after.keys()) {
+                    assert!(
+                        before.get(pos) == after.get(pos) || named.contains(pos),
+                        "{name} changed the highlight of {pos:?} without an event with seed {seed}"
+                    );
+                }
+
+                for event in events {
+                    match event {
+                        Event::Carved { from, to } => carve(&mut nodes, from, to),
+                        Event::Visited(pos) => assert!(
+                            visited.insert(pos),
+                            "{name} visited {pos:?} twice with seed {seed}"
+                        ),
+                        _ => {}
+                    }
+                }
+                assert_eq!(
+                    maze.visited_cells(),
+                    visited.len(),
+                    "{name} with seed {seed}"
+                );
+            }
+
+            assert_eq!(
+                nodes,
+                maze.nodes(),
+                "{name} carved walls it did not report with seed {seed}"
+            );
+            assert_eq!(visited.len(), width * height, "{name} with seed {seed}");
+        }
+    }
+}
+
+#[test]
+fn depth_first_search_reports_every_change() {
+    check_replay(DepthFirstSearch::new, "DepthFirstSearch");
+}
+
+#[test]
+fn binary_tree_reports_every_change() {
+    check_replay(BinaryTree::new, "BinaryTree");
+}
+
+#[test]
+fn hunt_and_kill_reports_every_change() {
+    check_replay(HuntAndKill::new, "HuntAndKill");
+}
+
+#[test]
+fn prim_reports_every_change() {
+    check_replay(Prim::new, "Prim");
+}
+
+#[test]
+fn kruskal_reports_every_change() {
+    check_replay(Kruskal::new, "Kruskal");
+}
+
+#[test]
+fn aldous_broder_reports_every_change() {
+    check_replay(AldousBroder::new, "AldousBroder");
+}
+
+#[test]
+fn prim_frontier_matches_highlights() {
+    let mut maze = Prim::new(10, 8);
+    maze.reset_with_seed(3);
+
+    let mut frontier: BTreeSet<Pos> = maze.highlights().iter().map(|&(pos, _)| pos).collect();
+    while !maze.complete() {
+        for event in maze.step_events() {
+            match event {
+                Event::Pushed(pos) => assert!(frontier.insert(pos)),
+                Event::Popped(pos) => assert!(frontier.remove(&pos)),
+                _ => {}
+            }
+        }
+
+        let highlighted: BTreeSet<Pos> = maze.highlights().iter().map(|&(pos, _)| pos).collect();
+        assert_eq!(frontier, highlighted);
+    }
+}
+
+#[test]
+fn kruskal_merges_every_set_once() {
+    let mut maze = Kruskal::new(7, 6);
+    maze.reset_with_seed(11);
+
+    let mut merged = HashSet::new();
+    while !maze.complete() {
+        for event in maze.step_events() {
+            if let Event::Merged { from, into } = event {
+                assert_ne!(from, into);
+                assert!(merged.insert(from), "set {from} was merged twice");
+            }
+        }
+    }
+
+    assert_eq!(merged.len(), 7 * 6 - 1);
+}
+
+#[test]
+fn stepping_matches_generating() {
+    let mut stepped = HuntAndKill::new(9, 9);
+    let mut generated = HuntAndKill::new(9, 9);
+    stepped.reset_with_seed(5);
+    generated.reset_with_seed(5);
+
+    while !stepped.complete() {
+        stepped.step_events();
+    }
+    generated.finish();
+
+    assert_eq!(stepped.nodes(), generated.nodes());
+}
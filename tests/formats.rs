@@ -0,0 +1,558 @@
+use rust_mazes::{
+    color::Color,
+    formats::*,
+    helpers::{Node, Pos},
+    maze::Maze,
+    mazes::DepthFirstSearch,
+    theme::Theme,
+    Algorithm,
+};
+
+fn generate(width: usize, height: usize, seed: u64) -> Vec<Vec<Node>> {
+    let mut maze = DepthFirstSearch::new(width, height);
+    maze.reset_with_seed(seed);
+    maze.finish();
+    maze.nodes().to_vec()
+}
+
+#[test]
+fn rasterizes_walls_and_margin() {
+    let nodes = generate(4, 3, 1);
+    let options = RasterOptions {
+        cell_size: 8,
+        wall_thickness: 2,
+        margin: 5,
+        wall: Color::BLACK,
+        passage: Color::WHITE,
+        background: Color::RED,
+    };
+
+    let canvas = rasterize(&nodes, &options);
+
+    assert_eq!(canvas.width(), 4 * 8 + 2 + 2 * 5);
+    assert_eq!(canvas.height(), 3 * 8 + 2 + 2 * 5);
+    assert_eq!(canvas.get_pixel(0, 0), Color::RED);
+    // The outer walls are always closed.
+    assert_eq!(canvas.get_pixel(5, 5), Color::BLACK);
+    assert_eq!(canvas.get_pixel(5 + 4 * 8 + 1, 5 + 3 * 8 + 1), Color::BLACK);
+    // The middle of every cell is a passage.
+    for x in 0..4 {
+        for y in 0..3 {
+            assert_eq!(canvas.get_pixel(5 + x * 8 + 5, 5 + y * 8 + 5), Color::WHITE);
+        }
+    }
+}
+
+#[test]
+fn writes_png() {
+    let canvas = rasterize(&generate(6, 6, 2), &RasterOptions::default());
+
+    let mut bytes = vec![];
+    canvas.write_png(&mut bytes).unwrap();
+
+    assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+}
+
+fn steps_to_finish(width: usize, height: usize, seed: u64) -> usize {
+    let mut maze = DepthFirstSearch::new(width, height);
+    maze.reset_with_seed(seed);
+    let mut steps = 0;
+    while !maze.complete() {
+        maze.generate();
+        steps += 1;
+    }
+    steps
+}
+
+#[test]
+fn rasterizes_progress() {
+    let mut maze = DepthFirstSearch::new(5, 5);
+    maze.reset_with_seed(3);
+    let options = RasterOptions {
+        margin: 0,
+        ..RasterOptions::default()
+    };
+
+    // Nothing has been visited before the first step.
+    let canvas = rasterize_progress(&maze, &options, &Theme::classic());
+    assert_eq!(canvas.get_pixel(45, 45), Color::BLACK);
+
+    maze.finish();
+    assert_eq!(
+        rasterize_progress(&maze, &options, &Theme::classic()).pixels(),
+        rasterize(maze.nodes(), &options).pixels()
+    );
+}
+
+#[test]
+fn records_gif() {
+    let steps = steps_to_finish(6, 4, 5);
+    let mut maze = DepthFirstSearch::new(6, 4);
+    maze.reset_with_seed(5);
+    let options = RecordOptions {
+        steps_per_frame: 4,
+        ..RecordOptions::default()
+    };
+
+    let mut bytes = vec![];
+    let frames = record_gif(&mut maze, &options, &mut bytes).unwrap();
+
+    assert_eq!(&bytes[..6], b"GIF89a");
+    assert_eq!(frames, 1 + steps.div_ceil(4));
+    assert!(maze.complete());
+}
+
+#[test]
+fn records_png_frames() {
+    let directory = std::env::temp_dir().join(format!("rust_mazes_frames_{}", std::process::id()));
+    let mut maze = DepthFirstSearch::new(3, 3);
+    maze.reset_with_seed(8);
+
+    let frames = record_png_frames(&mut maze, &RecordOptions::default(), &directory).unwrap();
+
+    assert_eq!(frames, 1 + steps_to_finish(3, 3, 8));
+    assert!(directory.join("frame_00000.png").exists());
+    assert!(directory
+        .join(format!("frame_{:05}.png", frames - 1))
+        .exists());
+    assert!(!directory.join(format!("frame_{frames:05}.png")).exists());
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn imports_rasterized_mazes() {
+    let block_style = RasterOptions {
+        cell_size: 6,
+        wall_thickness: 3,
+        margin: 0,
+        ..RasterOptions::default()
+    };
+    let thick_walls = RasterOptions {
+        cell_size: 8,
+        wall_thickness: 2,
+        margin: 5,
+        wall: Color::DARKBLUE,
+        passage: Color::RAYWHITE,
+        background: Color::GRAY,
+    };
+
+    for (i, options) in [RasterOptions::default(), block_style, thick_walls]
+        .iter()
+        .enumerate()
+    {
+        let nodes = generate(7 + i, 5, i as u64);
+
+        let mut bytes = vec![];
+        rasterize(&nodes, options).write_png(&mut bytes).unwrap();
+
+        assert_eq!(read_png(bytes.as_slice()).unwrap(), nodes);
+    }
+}
+
+#[test]
+fn rejects_images_without_a_maze() {
+    let mut canvas = Canvas::new(20, 20, Color::WHITE);
+    assert_eq!(from_canvas(&canvas), Err(ImportError::NoWalls));
+
+    canvas.fill_rect(3, 3, 1, 1, Color::BLACK);
+    assert_eq!(from_canvas(&canvas), Err(ImportError::Grid));
+}
+
+#[test]
+fn merges_wall_segments() {
+    // A 2x2 grid opened into a "U", so the only inner wall left is between the top cells.
+    let mut nodes = vec![vec![Node::new(); 2]; 2];
+    nodes[0][1].up = false;
+    nodes[1][1].up = false;
+    nodes[1][1].left = false;
+
+    let segments = wall_segments(&nodes);
+
+    // The top and bottom boundaries, the left and right boundaries, and the wall between the
+    // two top cells, which does not touch the bottom boundary.
+    assert_eq!(segments.len(), 5);
+    assert!(segments.contains(&Segment {
+        from: Pos::new(0, 0),
+        to: Pos::new(2, 0)
+    }));
+    assert!(segments.contains(&Segment {
+        from: Pos::new(1, 0),
+        to: Pos::new(1, 1)
+    }));
+}
+
+#[test]
+fn collapses_corridors() {
+    // The same "U" as above: a single corridor between two dead ends.
+    let mut nodes = vec![vec![Node::new(); 2]; 2];
+    nodes[0][1].up = false;
+    nodes[1][1].up = false;
+    nodes[1][1].left = false;
+
+    let full = passage_graph(&nodes, false);
+    assert_eq!(full.nodes.len(), 4);
+    assert_eq!(full.edges.len(), 3);
+
+    let collapsed = passage_graph(&nodes, true);
+    assert_eq!(collapsed.nodes, vec![Pos::new(0, 0), Pos::new(1, 0)]);
+    assert_eq!(
+        collapsed.edges,
+        vec![Edge {
+            from: Pos::new(0, 0),
+            to: Pos::new(1, 0),
+            length: 3
+        }]
+    );
+}
+
+#[test]
+fn collapsed_graph_is_a_tree() {
+    let nodes = generate(9, 7, 4);
+    let full = passage_graph(&nodes, false);
+    let collapsed = passage_graph(&nodes, true);
+
+    assert_eq!(full.edges.len(), 9 * 7 - 1);
+    assert_eq!(collapsed.edges.len(), collapsed.nodes.len() - 1);
+    assert_eq!(
+        collapsed
+            .edges
+            .iter()
+            .map(|edge| edge.length)
+            .sum::<usize>(),
+        full.edges.len()
+    );
+}
+
+#[test]
+fn writes_dot_and_edge_list() {
+    let graph = passage_graph(&generate(4, 4, 9), true);
+
+    let mut dot = vec![];
+    graph.write_dot(&mut dot).unwrap();
+    let dot = String::from_utf8(dot).unwrap();
+    assert!(dot.starts_with("graph maze {"));
+    assert!(dot.trim_end().ends_with('}'));
+    assert_eq!(dot.matches(" -- ").count(), graph.edges.len());
+    assert_eq!(dot.matches("pos=").count(), graph.nodes.len());
+
+    let mut list = vec![];
+    graph.write_edge_list(&mut list).unwrap();
+    let list = String::from_utf8(list).unwrap();
+    assert_eq!(list.lines().count(), graph.edges.len());
+    assert!(list
+        .lines()
+        .all(|line| line.split(' ').all(|value| value.parse::<usize>().is_ok())));
+}
+
+#[test]
+fn lays_out_tiles() {
+    // The same "U" as above, with the wall between the top cells sticking down from the top.
+    let mut nodes = vec![vec![Node::new(); 2]; 2];
+    nodes[0][1].up = false;
+    nodes[1][1].up = false;
+    nodes[1][1].left = false;
+
+    let tilemap = to_tilemap(&nodes, &TilemapOptions::default());
+    let mut csv = vec![];
+    tilemap.write_csv(&mut csv).unwrap();
+
+    assert_eq!((tilemap.width(), tilemap.height()), (5, 5));
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "1,1,1,1,1\n1,0,1,0,1\n1,0,1,0,1\n1,0,0,0,1\n1,1,1,1,1\n"
+    );
+
+    let autotiled = to_tilemap(
+        &nodes,
+        &TilemapOptions {
+            autotile: true,
+            ..TilemapOptions::default()
+        },
+    );
+    assert_eq!(autotiled.tile_count(), 17);
+    // The top left corner connects right and down.
+    assert_eq!(autotiled.get(0, 0), WALL_TILE + 2 + 4);
+    // The middle of the top edge is a junction with the inner wall.
+    assert_eq!(autotiled.get(2, 0), WALL_TILE + 2 + 4 + 8);
+    // The inner wall ends above the passage.
+    assert_eq!(autotiled.get(2, 2), WALL_TILE + 1);
+    assert_eq!(autotiled.get(1, 1), FLOOR_TILE);
+}
+
+#[test]
+fn writes_tmx() {
+    let tilemap = to_tilemap(&generate(3, 2, 2), &TilemapOptions::default());
+    let options = TilemapOptions {
+        tileset_image: Some("walls & floors.png".to_string()),
+        ..TilemapOptions::default()
+    };
+
+    let mut tmx = vec![];
+    tilemap.write_tmx(&options, &mut tmx).unwrap();
+    let tmx = String::from_utf8(tmx).unwrap();
+
+    assert!(tmx.contains(r#"width="7" height="5" tilewidth="16""#));
+    assert!(tmx.contains(r#"source="walls &amp; floors.png" width="32""#));
+    // Tiled numbers tiles from 1, so the top row is all walls.
+    assert!(tmx.contains("2,2,2,2,2,2,2,\n"));
+    assert!(tmx.contains("2,2,2,2,2,2,2\n  </data>"));
+}
+
+#[test]
+fn extrudes_walls() {
+    let nodes = generate(5, 4, 6);
+    let segments = wall_segments(&nodes).len();
+    let options = MeshOptions {
+        cell_size: 2.0,
+        wall_height: 3.0,
+        wall_thickness: 0.5,
+        floor_thickness: Some(1.0),
+    };
+
+    let mesh = extrude(&nodes, &options);
+
+    assert_eq!(mesh.vertices.len(), 8 * (segments + 1));
+    assert_eq!(mesh.triangles.len(), 12 * (segments + 1));
+    for axis in 0..3 {
+        let min = mesh
+            .vertices
+            .iter()
+            .map(|v| v[axis])
+            .fold(f32::MAX, f32::min);
+        let max = mesh
+            .vertices
+            .iter()
+            .map(|v| v[axis])
+            .fold(f32::MIN, f32::max);
+        let expected = [(-0.25, 10.25), (-0.25, 8.25), (-1.0, 3.0)][axis];
+        assert_eq!((min, max), expected);
+    }
+
+    let without_floor = extrude(
+        &nodes,
+        &MeshOptions {
+            floor_thickness: None,
+            ..options
+        },
+    );
+    assert_eq!(without_floor.triangles.len(), 12 * segments);
+}
+
+#[test]
+fn writes_obj_and_stl() {
+    let mesh = extrude(&generate(3, 3, 7), &MeshOptions::default());
+
+    let mut obj = vec![];
+    mesh.write_obj(&mut obj).unwrap();
+    let obj = String::from_utf8(obj).unwrap();
+    assert_eq!(
+        obj.lines().filter(|line| line.starts_with("v ")).count(),
+        mesh.vertices.len()
+    );
+    assert_eq!(
+        obj.lines().filter(|line| line.starts_with("f ")).count(),
+        mesh.triangles.len()
+    );
+
+    let mut stl = vec![];
+    mesh.write_stl(&mut stl).unwrap();
+    assert_eq!(stl.len(), 84 + 50 * mesh.triangles.len());
+    assert_eq!(
+        u32::from_le_bytes(stl[80..84].try_into().unwrap()) as usize,
+        mesh.triangles.len()
+    );
+}
+
+#[test]
+fn writes_svg_with_solution() {
+    let nodes = generate(5, 4, 3);
+    let options = SvgOptions {
+        show_solution: true,
+        ..SvgOptions::default()
+    }
+    .with_corners(&nodes);
+
+    let mut bytes = vec![];
+    write_svg(&nodes, &options, &mut bytes).unwrap();
+    let svg = String::from_utf8(bytes).unwrap();
+
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains("<polyline"));
+    assert!(svg.contains("<circle"));
+    assert_eq!(svg.matches("<line").count(), wall_segments(&nodes).len());
+}
+
+/// The maze drawn in the documentation of `to_text`.
+fn documented_maze() -> Vec<Vec<Node>> {
+    let mut nodes = vec![vec![Node::new(); 2]; 2];
+    nodes[1][0].left = false;
+    nodes[1][1].up = false;
+    nodes[1][1].left = false;
+    nodes
+}
+
+#[test]
+fn draws_ascii() {
+    let text = to_text(&documented_maze(), TextStyle::Ascii);
+
+    assert_eq!(
+        text,
+        "\
++--+--+
+|     |
++--+  +
+|     |
++--+--+
+"
+    );
+}
+
+#[test]
+fn draws_unicode_junctions() {
+    let mut nodes = vec![vec![Node::new(); 2]; 2];
+    nodes[0][1].up = false;
+    nodes[1][1].up = false;
+    nodes[1][1].left = false;
+
+    let text = to_text(&nodes, TextStyle::Unicode);
+
+    assert_eq!(
+        text,
+        "\
+┌──┬──┐
+│  │  │
+│  ╵  │
+│     │
+└─────┘
+"
+    );
+}
+
+#[test]
+fn parses_ascii_round_trip() {
+    let nodes = generate(9, 6, 4);
+
+    let text = to_text(&nodes, TextStyle::Ascii);
+
+    assert_eq!(parse_ascii(&text), Ok(nodes));
+    assert_eq!(
+        parse_ascii(&format!("\n\n{}\n", text.replace('\n', "  \n"))),
+        parse_ascii(&text)
+    );
+}
+
+#[test]
+fn rejects_malformed_ascii() {
+    assert_eq!(parse_ascii(" \n\n"), Err(ParseError::Empty));
+    assert_eq!(
+        parse_ascii("+--+--+\n|     |\n+--+--+\n|  |\n+--+--+\n"),
+        Err(ParseError::Size { line: 4 })
+    );
+    assert_eq!(
+        parse_ascii("+--+\n|  |\n+--+\n|  |\n"),
+        Err(ParseError::Size { line: 4 })
+    );
+    assert_eq!(
+        parse_ascii("\n+--+\n|  |\n"),
+        Err(ParseError::Size { line: 3 })
+    );
+    assert_eq!(
+        parse_ascii("\n\n+--+\n|  |\n+--+\n|  |\n\n"),
+        Err(ParseError::Size { line: 6 })
+    );
+    assert_eq!(
+        parse_ascii("+--+--+\n|  #  |\n+--+--+\n"),
+        Err(ParseError::Unexpected {
+            line: 2,
+            column: 4,
+            found: '#'
+        })
+    );
+    assert_eq!(
+        parse_ascii("+--+--+\n|      \n+--+--+\n"),
+        Err(ParseError::Size { line: 2 })
+    );
+    assert_eq!(
+        parse_ascii("+--+  +\n|     |\n+--+--+\n"),
+        Err(ParseError::OpenBoundary { line: 1, column: 5 })
+    );
+}
+
+fn saved_maze() -> SavedMaze {
+    let mut maze = DepthFirstSearch::new(7, 5);
+    maze.reset_with_seed(5);
+    maze.finish();
+
+    SavedMaze {
+        start: Some(Pos::new(0, 0)),
+        goal: Some(Pos::new(6, 4)),
+        ..SavedMaze::from_maze(&maze, Algorithm::named("depth-first-search").unwrap())
+    }
+}
+
+#[test]
+fn saves_json_round_trip() {
+    let saved = saved_maze();
+
+    let mut bytes = vec![];
+    saved.write_json(&mut bytes).unwrap();
+    let json = String::from_utf8(bytes).unwrap();
+
+    assert!(json.contains(r#""version": 1"#));
+    assert!(json.contains(r#""topology": "rectangular""#));
+    assert!(json.contains(r#""algorithm": "depth-first-search""#));
+    assert_eq!(SavedMaze::read_json(json.as_bytes()).unwrap(), saved);
+}
+
+#[test]
+fn saves_binary_round_trip() {
+    let saved = saved_maze();
+
+    let mut bytes = vec![];
+    saved.write_binary(&mut bytes).unwrap();
+
+    // Header, size, seed, name, start and goal, then 35 cells at four per byte.
+    let name = "depth-first-search".len();
+    assert_eq!(bytes.len(), 7 + 8 + 8 + 1 + name + 16 + 9);
+    assert_eq!(SavedMaze::read_binary(bytes.as_slice()).unwrap(), saved);
+
+    let bare = SavedMaze::new(saved.nodes.clone());
+    let mut bytes = vec![];
+    bare.write_binary(&mut bytes).unwrap();
+    assert_eq!(SavedMaze::read_binary(bytes.as_slice()).unwrap(), bare);
+}
+
+#[test]
+fn rejects_unsupported_versions() {
+    let json =
+        r#"{"version": 2, "topology": "rectangular", "width": 1, "height": 1, "rows": ["3"]}"#;
+    assert!(SavedMaze::read_json(json.as_bytes()).is_err());
+
+    let json =
+        r#"{"version": 1, "topology": "rectangular", "width": 2, "height": 1, "rows": ["3"]}"#;
+    assert!(SavedMaze::read_json(json.as_bytes()).is_err());
+
+    let mut bytes = vec![];
+    saved_maze().write_binary(&mut bytes).unwrap();
+    bytes[4] = 2;
+    assert!(SavedMaze::read_binary(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn rejects_empty_or_oversized_mazes_before_allocating() {
+    for (width, height) in [(1_000_000_000_000u64, 0), (0, 3), (1 << 20, 1 << 20)] {
+        let json = format!(
+            r#"{{"version": 1, "topology": "rectangular", "width": {width}, "height": {height}, "rows": []}}"#
+        );
+        assert!(SavedMaze::read_json(json.as_bytes()).is_err(), "{json}");
+    }
+
+    for (width, height) in [(u32::MAX, u32::MAX), (0, 3), (1 << 16, 1 << 16)] {
+        let mut bytes = b"MAZE\x01\x00\x00".to_vec();
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        assert!(SavedMaze::read_binary(bytes.as_slice()).is_err());
+    }
+}
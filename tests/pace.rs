@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use rust_mazes::pace::{Pacer, MAX_FRAME_TIME};
+
+/// Runs `frames` frames at `fps` and returns how many steps they were given.
+fn steps_at(fps: u32, frames: u32, steps_per_second: f64) -> usize {
+    let mut pacer = Pacer::new();
+    let frame = Duration::from_secs(1) / fps;
+    (0..frames)
+        .map(|_| pacer.advance(frame, steps_per_second))
+        .sum()
+}
+
+#[test]
+fn speed_does_not_depend_on_frame_rate() {
+    for steps_per_second in [1.0, 45.0, 1000.0, 120_000.0] {
+        let expected = steps_per_second as usize * 2;
+        for fps in [30, 60, 144, 240] {
+            let steps = steps_at(fps, fps * 2, steps_per_second);
+            assert!(
+                steps.abs_diff(expected) <= 1,
+                "{steps} steps in two seconds at {fps} fps, expected {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn carries_time_between_frames() {
+    let mut pacer = Pacer::new();
+    let frame = Duration::from_millis(100);
+
+    // At 4 steps per second a step is due every 250ms.
+    let steps: Vec<usize> = (0..10).map(|_| pacer.advance(frame, 4.0)).collect();
+    assert_eq!(steps, [0, 0, 1, 0, 1, 0, 0, 1, 0, 1]);
+}
+
+#[test]
+fn caps_long_frames() {
+    let mut pacer = Pacer::new();
+    let steps = pacer.advance(Duration::from_secs(10), 100.0);
+    assert_eq!(steps, (MAX_FRAME_TIME.as_secs_f64() * 100.0) as usize);
+}
+
+#[test]
+fn reset_forgets_leftover_time() {
+    let mut pacer = Pacer::new();
+    assert_eq!(pacer.advance(Duration::from_millis(90), 10.0), 0);
+    pacer.reset();
+    assert_eq!(pacer.advance(Duration::from_millis(90), 10.0), 0);
+    assert_eq!(pacer.advance(Duration::from_millis(20), 10.0), 1);
+}
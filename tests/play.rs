@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use rust_mazes::{
+    helpers::{Direction, Pos},
+    maze::Maze,
+    play::{Game, Move},
+    solve::solve,
+    Algorithm,
+};
+
+fn game(seed: u64) -> Game {
+    let mut maze = Algorithm::named("depth-first-search")
+        .unwrap()
+        .new_maze(8, 6);
+    maze.reset_with_seed(seed);
+    maze.finish();
+    Game::new(maze.nodes())
+}
+
+/// The move that goes from `from` to the neighboring cell `to`.
+fn towards(from: Pos, to: Pos) -> Move {
+    if to.x > from.x {
+        Move::Right
+    } else if to.x < from.x {
+        Move::Left
+    } else if to.y > from.y {
+        Move::Down
+    } else {
+        Move::Up
+    }
+}
+
+#[test]
+fn walks_the_shortest_path_to_the_exit() {
+    let mut game = game(1);
+    assert_eq!(game.position(), Pos::new(0, 0));
+    assert_eq!(game.goal, Pos::new(7, 5));
+
+    // The timer doesn't start before the first move.
+    game.tick(Duration::from_secs(1));
+    assert_eq!(game.elapsed(), Duration::ZERO);
+
+    let solution = game.solution().to_vec();
+    for pair in solution.windows(2) {
+        assert!(game.step(towards(pair[0], pair[1])));
+        game.tick(Duration::from_millis(100));
+    }
+
+    assert!(game.finished());
+    assert_eq!(game.moves(), game.optimal_moves());
+    assert_eq!(game.path(), solution);
+    // The step onto the exit stops the timer.
+    assert_eq!(
+        game.elapsed(),
+        Duration::from_millis(100) * (solution.len() as u32 - 2)
+    );
+
+    // Nothing moves once the exit is reached, and the replay plays both paths.
+    assert!(!game.step(towards(solution[solution.len() - 2], solution[0])));
+    game.replay();
+    assert_eq!(game.replay_shown(), Some((1, 1)));
+    game.tick(Duration::from_secs(60));
+    assert_eq!(game.replay_shown(), Some((solution.len(), solution.len())));
+}
+
+#[test]
+fn only_moves_where_there_is_no_wall() {
+    let mut maze = Algorithm::named("kruskal").unwrap().new_maze(6, 6);
+    maze.reset_with_seed(4);
+    maze.finish();
+    let nodes = maze.nodes();
+
+    for x in 0..6 {
+        for y in 0..6 {
+            let pos = Pos::new(x, y);
+            let open: Vec<Pos> = pos
+                .open_neighbors(nodes)
+                .iter()
+                .map(Direction::get_pos)
+                .collect();
+
+            for direction in [Move::Up, Move::Down, Move::Left, Move::Right] {
+                // Walk to `pos` along the shortest path, then try the move.
+                let mut game = Game::new(nodes);
+                if pos == game.goal {
+                    continue;
+                }
+                let path = solve(nodes, game.start, pos).unwrap();
+                for pair in path.windows(2) {
+                    game.step(towards(pair[0], pair[1]));
+                }
+                if game.position() != pos {
+                    // The shortest way there passes the exit, which ends the game.
+                    continue;
+                }
+
+                let target = match direction {
+                    Move::Up => y.checked_sub(1).map(|y| Pos::new(x, y)),
+                    Move::Down => Some(Pos::new(x, y + 1)),
+                    Move::Left => x.checked_sub(1).map(|x| Pos::new(x, y)),
+                    Move::Right => Some(Pos::new(x + 1, y)),
+                };
+                let expected = target.filter(|target| open.contains(target));
+
+                assert_eq!(game.step(direction), expected.is_some());
+                assert_eq!(game.position(), expected.unwrap_or(pos));
+            }
+        }
+    }
+}
+
+#[test]
+fn fog_reveals_the_cells_around_the_player() {
+    let mut game = game(2);
+    assert!(game.is_revealed(Pos::new(7, 5)));
+
+    game.fog = true;
+    assert!(game.is_revealed(Pos::new(2, 2)));
+    assert!(!game.is_revealed(Pos::new(3, 0)));
+    assert!(!game.is_revealed(Pos::new(7, 5)));
+
+    let solution = game.solution().to_vec();
+    for pair in solution.windows(2) {
+        game.step(towards(pair[0], pair[1]));
+    }
+    assert!(game.is_revealed(Pos::new(7, 5)));
+}
@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+use clap::ValueEnum;
+use rust_mazes::{
+    formats::Topology,
+    registry::{Bias, GENERATORS},
+    uniformity::test_uniformity,
+    Algorithm,
+};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn ids_are_unique_and_found_by_name() {
+    let mut ids = HashSet::new();
+    for &algorithm in Algorithm::all() {
+        assert!(
+            ids.insert(algorithm.id()),
+            "{algorithm:?} is registered twice"
+        );
+        assert_eq!(Algorithm::named(algorithm.id()), Some(algorithm));
+        assert_eq!(
+            Algorithm::from_str(algorithm.id(), false),
+            Ok(algorithm),
+            "the command line can't name {algorithm:?}"
+        );
+    }
+    assert_eq!(ids.len(), GENERATORS.len());
+    assert_eq!(Algorithm::named("bogosort"), None);
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Named {
+    algorithm: Algorithm,
+}
+
+#[test]
+fn algorithms_round_trip_through_toml() {
+    for &algorithm in Algorithm::all() {
+        let named = Named { algorithm };
+        let text = toml::to_string(&named).unwrap();
+        assert_eq!(text.trim(), format!("algorithm = \"{}\"", algorithm.id()));
+        assert_eq!(toml::from_str::<Named>(&text).unwrap(), named);
+    }
+
+    let err = toml::from_str::<Named>("algorithm = \"bogosort\"").unwrap_err();
+    assert!(err.to_string().contains("unknown algorithm"), "{err}");
+}
+
+#[test]
+fn every_generator_makes_perfect_mazes() {
+    for &algorithm in Algorithm::all() {
+        let generator = algorithm.generator();
+        assert!(generator.topologies.contains(&Topology::Rectangular));
+        assert!(generator.default_speed > 0);
+
+        for (width, height) in [(1, 1), (1, 5), (6, 1), (9, 7)] {
+            let mut maze = algorithm.new_maze(width, height);
+            for seed in 0..5 {
+                maze.reset_with_seed(seed);
+                maze.finish();
+                if let Err(err) = maze.validate() {
+                    panic!("{algorithm:?} made an invalid {width}x{height} maze with seed {seed}: {err}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn bias_matches_uniformity() {
+    for &algorithm in Algorithm::all() {
+        let report = test_uniformity(
+            |width, height| algorithm.new_maze(width, height),
+            3,
+            3,
+            20_000,
+            0x5eed,
+        );
+        let uniform = algorithm.generator().bias == Bias::Uniform;
+        assert_eq!(report.is_uniform(0.001), uniform, "{algorithm:?}: {report}");
+    }
+}
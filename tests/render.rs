@@ -0,0 +1,87 @@
+use std::collections::BTreeSet;
+
+use rust_mazes::{
+    formats::{wall_segments, Segment},
+    helpers::{Node, Pos},
+    maze::Maze,
+    render::{cell_walls, Repaint},
+    Algorithm,
+};
+
+/// Whether the wall `segment` covers any of the cell at `pos`, even just a corner.
+fn touches(segment: Segment, pos: Pos) -> bool {
+    let (from, to) = (segment.from, segment.to);
+    from.x <= pos.x + 1 && pos.x <= to.x && from.y <= pos.y + 1 && pos.y <= to.y
+}
+
+/// Every grid line between two neighboring corners that `segments` cover.
+fn unit_walls(segments: impl IntoIterator<Item = Segment>) -> BTreeSet<(Pos, Pos)> {
+    let mut walls = BTreeSet::new();
+    for Segment { from, to } in segments {
+        for x in from.x..to.x {
+            walls.insert((Pos::new(x, from.y), Pos::new(x + 1, from.y)));
+        }
+        for y in from.y..to.y {
+            walls.insert((Pos::new(from.x, y), Pos::new(from.x, y + 1)));
+        }
+    }
+    walls
+}
+
+fn all_cells(nodes: &[Vec<Node>]) -> impl Iterator<Item = Pos> + '_ {
+    nodes
+        .iter()
+        .enumerate()
+        .flat_map(|(x, column)| (0..column.len()).map(move |y| Pos::new(x, y)))
+}
+
+#[test]
+fn cell_walls_draw_every_wall_once() {
+    for &algorithm in Algorithm::all() {
+        let mut maze = algorithm.new_maze(9, 7);
+        maze.reset_with_seed(2);
+        maze.finish();
+        let nodes = maze.nodes();
+
+        let drawn: Vec<Segment> = all_cells(nodes)
+            .flat_map(|pos| cell_walls(nodes, pos))
+            .collect();
+        assert_eq!(drawn.len(), unit_walls(drawn.clone()).len(), "{algorithm}");
+        assert_eq!(
+            unit_walls(drawn),
+            unit_walls(wall_segments(nodes)),
+            "{algorithm}"
+        );
+    }
+}
+
+#[test]
+fn repaints_every_wall_over_the_refilled_cells() {
+    for &algorithm in Algorithm::all() {
+        let mut maze = algorithm.new_maze(8, 6);
+        maze.reset_with_seed(5);
+
+        while !maze.complete() {
+            let dirty: Vec<Pos> = maze
+                .step_events()
+                .into_iter()
+                .flat_map(|event| event.cells())
+                .collect();
+            let repaint = Repaint::new(&dirty, 8, 6);
+            assert!(dirty.iter().all(|pos| repaint.fill.contains(pos)));
+
+            // A full repaint draws the walls of every visited cell, so every one of them that
+            // a refill covers any of must be drawn again.
+            let nodes = maze.nodes();
+            for pos in all_cells(nodes).filter(|&pos| maze.is_visited(pos)) {
+                for segment in cell_walls(nodes, pos) {
+                    let covered = repaint.fill.iter().any(|&fill| touches(segment, fill));
+                    assert!(
+                        !covered || repaint.walls.contains(&pos),
+                        "{algorithm} doesn't redraw {segment:?} of {pos:?} over the refill"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use rust_mazes::{
+    helpers::{Node, Pos},
+    maze::Maze,
+    mazes::HuntAndKill,
+    solve::solve,
+};
+
+#[test]
+fn solves_between_any_cells() {
+    let mut maze = HuntAndKill::new(9, 7);
+    maze.reset_with_seed(7);
+    maze.finish();
+
+    let start = Pos::new(0, 0);
+    let goal = Pos::new(8, 6);
+    let path = solve(maze.nodes(), start, goal).unwrap();
+
+    assert_eq!(path.first(), Some(&start));
+    assert_eq!(path.last(), Some(&goal));
+    for step in path.windows(2) {
+        let reachable: Vec<Pos> = step[0]
+            .open_neighbors(maze.nodes())
+            .iter()
+            .map(|neighbor| neighbor.get_pos())
+            .collect();
+        assert!(reachable.contains(&step[1]));
+    }
+}
+
+#[test]
+fn no_path_through_walls() {
+    let nodes = vec![vec![Node::new(); 3]; 3];
+
+    assert_eq!(
+        solve(&nodes, Pos::new(0, 0), Pos::new(0, 0)),
+        Some(vec![Pos::new(0, 0)])
+    );
+    assert_eq!(solve(&nodes, Pos::new(0, 0), Pos::new(2, 2)), None);
+    assert_eq!(solve(&nodes, Pos::new(0, 0), Pos::new(3, 0)), None);
+}
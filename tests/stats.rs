@@ -0,0 +1,40 @@
+use rust_mazes::{formats::parse_ascii, stats::MazeStats};
+
+#[test]
+fn counts_cell_shapes() {
+    let nodes = parse_ascii(
+        "\
++--+--+--+
+|        |
++--+  +  +
+|     |  |
++--+--+--+
+",
+    )
+    .unwrap();
+
+    let stats = MazeStats::of(&nodes);
+    assert_eq!(stats.cells, 6);
+    assert_eq!(stats.dead_ends, 3);
+    assert_eq!(stats.straights, 0);
+    assert_eq!(stats.turns, 2);
+    assert_eq!(stats.junctions, 1);
+    assert_eq!(stats.solution_length, Some(4));
+}
+
+#[test]
+fn every_cell_has_a_shape_in_perfect_mazes() {
+    for &algorithm in rust_mazes::Algorithm::all() {
+        let mut maze = algorithm.new_maze(12, 7);
+        maze.reset_with_seed(5);
+        maze.finish();
+
+        let stats = MazeStats::of(maze.nodes());
+        assert_eq!(
+            stats.dead_ends + stats.straights + stats.turns + stats.junctions,
+            stats.cells,
+            "{algorithm:?}"
+        );
+        assert!(stats.solution_length.is_some(), "{algorithm:?}");
+    }
+}
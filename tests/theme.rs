@@ -0,0 +1,65 @@
+use rust_mazes::{
+    color::Color,
+    theme::{Highlight, Theme},
+};
+
+#[test]
+fn builtin_themes_have_unique_names() {
+    let themes = Theme::builtin();
+    for (i, theme) in themes.iter().enumerate() {
+        assert!(themes[i + 1..].iter().all(|other| other.name != theme.name));
+        assert_eq!(Theme::named(&theme.name).as_ref(), Some(theme));
+    }
+    assert_eq!(Theme::default(), Theme::classic());
+}
+
+#[test]
+fn builtin_themes_round_trip_through_toml() {
+    for theme in Theme::builtin() {
+        assert_eq!(Theme::from_toml(&theme.to_toml()).unwrap(), theme);
+    }
+}
+
+#[test]
+fn missing_colors_fall_back_to_classic() {
+    let theme = Theme::from_toml(
+        r##"
+name = "sunset"
+passage = "#ffeedd"
+trail = "#ff660080"
+"##,
+    )
+    .unwrap();
+
+    assert_eq!(theme.name, "sunset");
+    assert_eq!(theme.passage, Color::new(0xff, 0xee, 0xdd, 0xff));
+    assert_eq!(
+        theme.highlight(Highlight::Trail),
+        Color::new(0xff, 0x66, 0x00, 0x80)
+    );
+    assert_eq!(theme.wall, Theme::classic().wall);
+    assert_eq!(theme.highlight(Highlight::Active), Theme::classic().active);
+}
+
+#[test]
+fn rejects_malformed_colors() {
+    for color in ["ffffff", "#fff", "#gggggg", "#ffffff0", "#ffé000"] {
+        let toml = format!("wall = {color:?}");
+        assert!(Theme::from_toml(&toml).is_err(), "accepted {color}");
+    }
+}
+
+#[test]
+fn names_unnamed_themes_after_their_file() {
+    let path = std::env::temp_dir().join(format!("rust_mazes_{}_ocean.toml", std::process::id()));
+    std::fs::write(&path, r##"passage = "#003355""##).unwrap();
+
+    let theme = Theme::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        theme.name,
+        format!("rust_mazes_{}_ocean", std::process::id())
+    );
+    assert_eq!(theme.passage, Color::new(0x00, 0x33, 0x55, 0xff));
+}
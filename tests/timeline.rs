@@ -0,0 +1,138 @@
+use rust_mazes::{maze::Maze, mazes::*, timeline::Timeline};
+
+/// A maze generated `steps` steps from `seed` without a timeline.
+fn stepped<M: Maze>(mut maze: M, seed: u64, steps: usize) -> M {
+    maze.reset_with_seed(seed);
+    for _ in 0..steps {
+        maze.generate();
+    }
+    maze
+}
+
+#[test]
+fn steps_back_to_earlier_states() {
+    let mut timeline = Timeline::new(Prim::new(8, 6));
+    timeline.reset_with_seed(4);
+    for _ in 0..30 {
+        assert!(timeline.step_forward());
+    }
+
+    for position in (0..30).rev() {
+        assert!(timeline.step_back());
+        assert_eq!(timeline.position(), position);
+        assert_eq!(
+            timeline.maze().nodes(),
+            stepped(Prim::new(8, 6), 4, position).nodes()
+        );
+    }
+
+    assert!(!timeline.step_back());
+    assert_eq!(timeline.recorded(), 30);
+}
+
+#[test]
+fn seeks_anywhere_in_a_long_history() {
+    // Aldous-Broder takes many steps, so the checkpoints get thinned out several times.
+    let mut timeline = Timeline::new(AldousBroder::new(40, 40));
+    timeline.reset_with_seed(9);
+    while timeline.step_forward() {}
+
+    let total = timeline.recorded();
+    assert!(total > 64 * 64 * 2, "only took {total} steps");
+    assert!(timeline.maze().complete());
+
+    for position in [0, 1, total / 3, total / 2 + 7, total - 1, total] {
+        timeline.seek(position);
+        assert_eq!(timeline.position(), position);
+        assert_eq!(
+            timeline.maze().nodes(),
+            stepped(AldousBroder::new(40, 40), 9, position).nodes()
+        );
+    }
+
+    // Seeking past the end stops at the last recorded step.
+    timeline.seek(total + 100);
+    assert_eq!(timeline.position(), total);
+}
+
+#[test]
+fn replays_forward_after_stepping_back() {
+    let mut timeline = Timeline::new(DepthFirstSearch::new(6, 6));
+    timeline.reset_with_seed(2);
+    for _ in 0..40 {
+        timeline.step_forward();
+    }
+
+    timeline.seek(10);
+    for _ in 0..40 {
+        timeline.step_forward();
+    }
+
+    assert_eq!(timeline.position(), 50);
+    assert_eq!(timeline.recorded(), 50);
+    assert_eq!(
+        timeline.maze().nodes(),
+        stepped(DepthFirstSearch::new(6, 6), 2, 50).nodes()
+    );
+}
+
+#[test]
+fn stops_at_completion() {
+    let mut timeline = Timeline::new(BinaryTree::new(3, 3));
+    timeline.reset_with_seed(1);
+
+    while timeline.step_forward() {}
+
+    assert_eq!(timeline.recorded(), 9);
+    assert!(!timeline.step_forward());
+    assert_eq!(timeline.position(), 9);
+}
+
+#[test]
+fn collects_the_cells_steps_change() {
+    let mut timeline = Timeline::new(DepthFirstSearch::new(6, 6));
+    timeline.reset_with_seed(3);
+    // Anything may have changed after a reset.
+    assert_eq!(timeline.take_changes(), None);
+    assert_eq!(timeline.take_changes(), Some(vec![]));
+
+    let mut maze = timeline.maze().clone();
+    let expected: Vec<_> = (0..3)
+        .flat_map(|_| maze.step_events())
+        .flat_map(|event| event.cells())
+        .collect();
+    for _ in 0..3 {
+        timeline.step_forward();
+    }
+    assert_eq!(timeline.take_changes(), Some(expected));
+
+    timeline.step_back();
+    assert_eq!(timeline.take_changes(), None);
+}
+
+#[test]
+fn keeps_fewer_checkpoints_of_larger_mazes() {
+    // Room for four snapshots of a 10 by 10 maze.
+    let mut timeline = Timeline::with_checkpoint_cells(AldousBroder::new(10, 10), 400);
+    timeline.reset_with_seed(6);
+    while timeline.step_forward() {}
+
+    let recorded = timeline.recorded();
+    assert!(recorded > 64 * 4, "only took {recorded} steps");
+    assert!(timeline.checkpoints() <= 4);
+
+    for position in [0, 1, recorded / 3, recorded - 1] {
+        timeline.seek(position);
+        assert_eq!(
+            timeline.maze().nodes(),
+            stepped(AldousBroder::new(10, 10), 6, position).nodes()
+        );
+    }
+
+    // Resetting keeps the budget.
+    timeline.reset_with_seed(7);
+    while timeline.step_forward() {}
+    assert!(timeline.checkpoints() <= 4);
+
+    assert_eq!(Timeline::new(Prim::new(8, 6)).checkpoints(), 1);
+}
@@ -0,0 +1,57 @@
+use rust_mazes::{maze::Maze, mazes::*, uniformity::*};
+
+const SAMPLES: usize = 20_000;
+const SEED: u64 = 0x5eed;
+const SIGNIFICANCE: f64 = 0.001;
+
+fn report<M: Maze>(new: fn(usize, usize) -> M, name: &str) -> UniformityReport {
+    let report = test_uniformity(new, 3, 3, SAMPLES, SEED);
+    println!("{name}: {report}");
+    report
+}
+
+#[test]
+fn enumerates_spanning_trees() {
+    assert_eq!(spanning_trees(1, 1).len(), 1);
+    assert_eq!(spanning_trees(1, 4).len(), 1);
+    assert_eq!(spanning_trees(2, 2).len(), 4);
+    assert_eq!(spanning_trees(2, 3).len(), 15);
+    assert_eq!(spanning_trees(3, 3).len(), 192);
+}
+
+#[test]
+fn aldous_broder_is_uniform() {
+    let report = report(AldousBroder::new, "AldousBroder");
+
+    assert!(report.is_uniform(SIGNIFICANCE), "{report}");
+    assert_eq!(report.unseen, 0);
+}
+
+#[test]
+fn binary_tree_is_biased() {
+    let report = report(BinaryTree::new, "BinaryTree");
+
+    // Every cell but the top left one opens either its up or left wall, so only 2^4 of the
+    // 192 trees can come up at all.
+    assert!(!report.is_uniform(SIGNIFICANCE), "{report}");
+    assert_eq!(report.unseen, 192 - 16);
+}
+
+#[test]
+fn depth_first_search_is_biased() {
+    let report = report(DepthFirstSearch::new, "DepthFirstSearch");
+
+    assert!(!report.is_uniform(SIGNIFICANCE), "{report}");
+}
+
+/// Prints the report of every generator, run with `cargo test -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn report_all_generators() {
+    report(DepthFirstSearch::new, "DepthFirstSearch");
+    report(BinaryTree::new, "BinaryTree");
+    report(HuntAndKill::new, "HuntAndKill");
+    report(Prim::new, "Prim");
+    report(Kruskal::new, "Kruskal");
+    report(AldousBroder::new, "AldousBroder");
+}
@@ -0,0 +1,174 @@
+use rust_mazes::{
+    helpers::{Node, Pos},
+    maze::Maze,
+    mazes::*,
+    validate::{validate, ValidationError},
+};
+
+const SIZES: [(usize, usize); 8] = [
+    (1, 1),
+    (1, 7),
+    (7, 1),
+    (2, 2),
+    (3, 3),
+    (8, 5),
+    (5, 8),
+    (24, 17),
+];
+const SEEDS: u64 = 25;
+
+fn check_generator<M: Maze>(new: fn(usize, usize) -> M, name: &str) {
+    for (width, height) in SIZES {
+        let mut maze = new(width, height);
+
+        for seed in 0..SEEDS {
+            maze.reset_with_seed(seed);
+
+            // Aldous-Broder's random walk is the slowest to finish, but covers a grid of
+            // this size in far fewer steps than this on average.
+            let max_steps = 1000 * width * height * (width + height);
+            let mut steps = 0;
+            while !maze.complete() {
+                assert!(
+                    steps < max_steps,
+                    "{name} did not complete a {width}x{height} maze with seed {seed} within {max_steps} steps"
+                );
+
+                maze.generate();
+                steps += 1;
+            }
+
+            if let Err(err) = maze.validate() {
+                panic!("{name} generated an invalid {width}x{height} maze with seed {seed}: {err}");
+            }
+        }
+    }
+}
+
+#[test]
+fn depth_first_search_is_perfect() {
+    check_generator(DepthFirstSearch::new, "DepthFirstSearch");
+}
+
+#[test]
+fn binary_tree_is_perfect() {
+    check_generator(BinaryTree::new, "BinaryTree");
+}
+
+#[test]
+fn hunt_and_kill_is_perfect() {
+    check_generator(HuntAndKill::new, "HuntAndKill");
+}
+
+#[test]
+fn prim_is_perfect() {
+    check_generator(Prim::new, "Prim");
+}
+
+#[test]
+fn kruskal_is_perfect() {
+    check_generator(Kruskal::new, "Kruskal");
+}
+
+#[test]
+fn aldous_broder_is_perfect() {
+    check_generator(AldousBroder::new, "AldousBroder");
+}
+
+#[test]
+fn same_seed_generates_same_maze() {
+    let mut first = Prim::new(12, 9);
+    let mut second = Prim::new(12, 9);
+    first.reset_with_seed(42);
+    second.reset_with_seed(42);
+
+    first.finish();
+    second.finish();
+
+    for (a, b) in first
+        .nodes()
+        .iter()
+        .flatten()
+        .zip(second.nodes().iter().flatten())
+    {
+        assert_eq!((a.up, a.left), (b.up, b.left));
+    }
+}
+
+/// A 3x2 grid carved as a "U": down the left column, along the bottom, up the right column.
+fn u_shape() -> Vec<Vec<Node>> {
+    let mut nodes = vec![vec![Node::new(); 2]; 3];
+    nodes[0][1].up = false;
+    nodes[1][1].left = false;
+    nodes[2][1].left = false;
+    nodes[2][1].up = false;
+    nodes
+}
+
+#[test]
+fn accepts_spanning_tree() {
+    let mut nodes = u_shape();
+    nodes[1][1].up = false;
+
+    assert_eq!(validate(&nodes), Ok(()));
+}
+
+#[test]
+fn rejects_empty_grid() {
+    assert_eq!(validate(&[]), Err(ValidationError::Empty));
+    assert_eq!(validate(&[vec![]]), Err(ValidationError::Empty));
+}
+
+#[test]
+fn rejects_uncarved_grid() {
+    let nodes = vec![vec![Node::new(); 3]; 3];
+
+    assert_eq!(
+        validate(&nodes),
+        Err(ValidationError::PassageCount {
+            expected: 8,
+            found: 0
+        })
+    );
+}
+
+#[test]
+fn rejects_open_boundary() {
+    let mut nodes = u_shape();
+    nodes[1][0].up = false;
+
+    assert_eq!(
+        validate(&nodes),
+        Err(ValidationError::OpenBoundary(Pos::new(1, 0)))
+    );
+}
+
+/// A 3x2 grid where the 2x2 square of cells with its corner at `(x, 0)` is carved into a loop
+/// and the remaining column is connected on its own, so the passage count is still right.
+fn loop_at(x: usize) -> Vec<Vec<Node>> {
+    let mut nodes = vec![vec![Node::new(); 2]; 3];
+    nodes[x + 1][0].left = false;
+    nodes[x + 1][1].left = false;
+    nodes[x][1].up = false;
+    nodes[x + 1][1].up = false;
+
+    let other = if x == 0 { 2 } else { 0 };
+    nodes[other][1].up = false;
+    nodes
+}
+
+#[test]
+fn rejects_cycle() {
+    assert!(matches!(
+        validate(&loop_at(0)),
+        Err(ValidationError::Cycle(_))
+    ));
+}
+
+#[test]
+fn rejects_unreachable_cell() {
+    assert_eq!(
+        validate(&loop_at(1)),
+        Err(ValidationError::Unreachable(Pos::new(1, 0)))
+    );
+}